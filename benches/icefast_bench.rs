@@ -158,6 +158,83 @@ define_size_benches!(524288, parallel_kb_512, large);
 define_size_benches!(1048576, parallel_mb_1, large);
 define_size_benches!(33554432, parallel_mb_32, large);
 
+////////////////////////////////////////////////////////////////////
+// Compares the kernel backends at every level, serially on 64 KB, for the runtime-level `Ice`
+// and for the fixed-level types, whose portable kernel is unrolled at every level. `batch`
+// compares them per batch size, which is what `Backend::kernel` decides on.
+#[divan::bench_group(sample_count = 500)]
+mod backends {
+    use super::*;
+    use icefast::{Backend, Ice1, Ice2, Ice3, Ice4, Ice5, Ice6, Ice7, Ice8, IceFixed, IceThin};
+
+    const LEN: usize = 65536;
+    static KEY64: [u8; 64] = [0x5A; 64];
+
+    #[divan::bench(args = Backend::ALL, consts = [0, 1, 2, 3, 4, 5, 6, 7, 8])]
+    fn ice<const LEVEL: usize>(bencher: divan::Bencher, backend: Backend) {
+        if !backend.is_available() {
+            return;
+        }
+        let ice = Ice::builder()
+            .level(LEVEL)
+            .key(&KEY64)
+            .backend(backend)
+            .build()
+            .unwrap();
+        bencher
+            .counter(BytesCount::new(LEN))
+            .with_inputs(|| vec![0u8; LEN])
+            .bench_refs(|b| ice.decrypt(b));
+    }
+
+    #[divan::bench(args = Backend::ALL, types = [IceThin, Ice1, Ice2, Ice3, Ice4, Ice5, Ice6, Ice7, Ice8])]
+    fn fixed<T: FixedLevel>(bencher: divan::Bencher, backend: Backend) {
+        if !backend.is_available() {
+            return;
+        }
+        let ice = T::build(backend);
+        bencher
+            .counter(BytesCount::new(LEN))
+            .with_inputs(|| vec![0u8; LEN])
+            .bench_refs(|b| ice.decrypt(b));
+    }
+
+    #[divan::bench(args = Backend::ALL, consts = [8, 16, 32, 64, 128, 256, 512])]
+    fn batch<const B: usize>(bencher: divan::Bencher, backend: Backend) {
+        if !backend.is_available() {
+            return;
+        }
+        let ice: Ice1 = Ice::builder()
+            .key(&KEY64)
+            .backend(backend)
+            .build_fixed()
+            .unwrap();
+        bencher
+            .counter(BytesCount::new(B * 8))
+            .with_inputs(|| vec![0u8; B * 8])
+            .bench_refs(|b| ice.decrypt(b));
+    }
+
+    trait FixedLevel: Sync {
+        fn build(backend: Backend) -> Self;
+        fn decrypt(&self, data: &mut [u8]);
+    }
+
+    impl<const ROUNDS: usize> FixedLevel for IceFixed<ROUNDS> {
+        fn build(backend: Backend) -> Self {
+            Ice::builder()
+                .key(&KEY64)
+                .backend(backend)
+                .build_fixed()
+                .unwrap()
+        }
+
+        fn decrypt(&self, data: &mut [u8]) {
+            IceFixed::decrypt(self, data);
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////
 // This benches what decrypting many random length files looks like
 // when decrypting packaged files of mixed lengths based upon a weighted
@@ -205,7 +282,7 @@ fn decrypt_5000_random_files_auto(bencher: divan::Bencher) {
     let mut file_buffers = Vec::with_capacity(5000);
     for _ in 0..5000 {
         let size = random_file_size(&mut rng);
-        let blocks = (size + 7) / 8;
+        let blocks = size.div_ceil(8);
         let buf = CIPHER_TEXT_8_LEVEL0.repeat(blocks);
         file_buffers.push(buf);
     }
@@ -229,7 +306,7 @@ fn par_decrypt_5000_random_files_auto(bencher: divan::Bencher) {
     let mut file_buffers = Vec::with_capacity(5000);
     for _ in 0..5000 {
         let size = random_file_size(&mut rng);
-        let blocks = (size + 7) / 8;
+        let blocks = size.div_ceil(8);
         let buf = CIPHER_TEXT_8_LEVEL0.repeat(blocks);
         file_buffers.push(buf);
     }
@@ -251,7 +328,7 @@ fn decrypt_5000_random_files_serial(bencher: divan::Bencher) {
     let mut file_buffers = Vec::with_capacity(5000);
     for _ in 0..5000 {
         let size = random_file_size(&mut rng);
-        let blocks = (size + 7) / 8;
+        let blocks = size.div_ceil(8);
         let buf = CIPHER_TEXT_8_LEVEL0.repeat(blocks);
         file_buffers.push(buf);
     }
@@ -275,7 +352,7 @@ fn par_decrypt_5000_random_files_serial(bencher: divan::Bencher) {
     let mut file_buffers = Vec::with_capacity(5000);
     for _ in 0..5000 {
         let size = random_file_size(&mut rng);
-        let blocks = (size + 7) / 8;
+        let blocks = size.div_ceil(8);
         let buf = CIPHER_TEXT_8_LEVEL0.repeat(blocks);
         file_buffers.push(buf);
     }
@@ -297,7 +374,7 @@ fn decrypt_5000_random_files_par(bencher: divan::Bencher) {
     let mut file_buffers = Vec::with_capacity(5000);
     for _ in 0..5000 {
         let size = random_file_size(&mut rng);
-        let blocks = (size + 7) / 8;
+        let blocks = size.div_ceil(8);
         let buf = CIPHER_TEXT_8_LEVEL0.repeat(blocks);
         file_buffers.push(buf);
     }
@@ -321,7 +398,7 @@ fn par_decrypt_5000_random_files_par(bencher: divan::Bencher) {
     let mut file_buffers = Vec::with_capacity(5000);
     for _ in 0..5000 {
         let size = random_file_size(&mut rng);
-        let blocks = (size + 7) / 8;
+        let blocks = size.div_ceil(8);
         let buf = CIPHER_TEXT_8_LEVEL0.repeat(blocks);
         file_buffers.push(buf);
     }
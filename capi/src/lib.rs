@@ -0,0 +1,7 @@
+//! Links [`icefast::capi`] into `libicefast.so` (or the platform's equivalent) and
+//! `libicefast.a`, declared by `include/icefast.h`.
+//!
+//! This crate only exists so the main crate can stay an `rlib`: C library crate types cannot
+//! be built without `std`.
+
+pub use icefast::capi::*;
@@ -0,0 +1,98 @@
+#ifndef ICEFAST_H
+#define ICEFAST_H
+
+/* Generated by cbindgen from src/capi.rs; do not edit. */
+
+#include <stddef.h>
+
+typedef struct ice_key_struct ICE_KEY;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Creates an ICE key of level `n`, where `n < 1` selects Thin-ICE.
+
+ The key must be set with `ice_key_set` before use. Unlike `ice.c`, which accepts any `n`,
+ this returns `NULL` for levels above 8, so callers must check the result.
+ */
+ICE_KEY *ice_key_create(int n);
+
+/*
+ Zeroes and frees an ICE key. `NULL` is ignored.
+
+ # Safety
+ `ik` must be `NULL` or a key from `ice_key_create` that has not been destroyed.
+ */
+void ice_key_destroy(ICE_KEY *ik);
+
+/*
+ Sets the key of `ik` from `ice_key_key_size(ik)` bytes at `k`.
+
+ # Safety
+ `ik` must be a live key and `k` must point to `ice_key_key_size(ik)` readable bytes.
+ */
+void ice_key_set(ICE_KEY *ik, const unsigned char *k);
+
+/*
+ Encrypts the 8-byte block at `ptxt` into `ctxt`, which may be the same buffer.
+
+ # Safety
+ `ik` must be a live key, `ptxt` must point to 8 readable bytes and `ctxt` to 8 writable
+ bytes.
+ */
+void ice_key_encrypt(const ICE_KEY *ik, const unsigned char *ptxt, unsigned char *ctxt);
+
+/*
+ Decrypts the 8-byte block at `ctxt` into `ptxt`, which may be the same buffer.
+
+ # Safety
+ `ik` must be a live key, `ctxt` must point to 8 readable bytes and `ptxt` to 8 writable
+ bytes.
+ */
+void ice_key_decrypt(const ICE_KEY *ik, const unsigned char *ctxt, unsigned char *ptxt);
+
+/*
+ Returns the key size of `ik` in bytes.
+
+ # Safety
+ `ik` must be a live key.
+ */
+int ice_key_key_size(const ICE_KEY *ik);
+
+/*
+ Returns the block size of `ik` in bytes, which is always 8.
+
+ # Safety
+ `ik` must be a live key.
+ */
+int ice_key_block_size(const ICE_KEY *ik);
+
+/*
+ Encrypts `len` bytes at `data` in-place, serially or in parallel depending on `len`.
+
+ Returns 0 on success, or -1 without touching `data` if `len` is not a multiple of 8.
+
+ # Safety
+ `ik` must be a live key and `data` must point to `len` writable bytes, or may be `NULL` if
+ `len` is 0.
+ */
+int ice_key_encrypt_buffer(const ICE_KEY *ik, unsigned char *data, size_t len);
+
+/*
+ Decrypts `len` bytes at `data` in-place, serially or in parallel depending on `len`.
+
+ Returns 0 on success, or -1 without touching `data` if `len` is not a multiple of 8.
+
+ # Safety
+ `ik` must be a live key and `data` must point to `len` writable bytes, or may be `NULL` if
+ `len` is 0.
+ */
+int ice_key_decrypt_buffer(const ICE_KEY *ik, unsigned char *data, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ICEFAST_H */
@@ -0,0 +1,123 @@
+//! Explicit kernels for the batched Feistel network, selected at runtime or per instance.
+//!
+//! The portable kernel is plain Rust that the compiler may auto-vectorize for the build's
+//! target CPU. On x86 the AVX2 and AVX-512 kernels evaluate the round function for 8 or 16
+//! blocks at once with `vpgatherdd` S-box lookups, so a binary built for a baseline CPU still
+//! uses them where the host supports them. The best available backend is detected once, with
+//! `is_x86_feature_detected!` under `std` or from the compile-time target features otherwise,
+//! and can be overridden per instance with [`IceBuilder::backend`](crate::IceBuilder::backend).
+//!
+//! Every kernel but [`Backend::ConstantTime`] indexes the S-box tables with key- and
+//! data-dependent values, which can leak through the cache to code sharing the CPU. The
+//! constant-time backend computes the S-boxes in bitsliced form instead. It is about ten times
+//! slower, so it is never selected automatically.
+
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::icefast::IceSubkey;
+
+mod bitsliced;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
+
+/// Runs every round of `keysched` over the blocks whose halves are `l` and `r`.
+///
+/// # Safety
+/// The CPU must support the backend the kernel belongs to.
+pub(crate) type Kernel =
+    unsafe fn(keysched: &[IceSubkey], decrypt: bool, l: &mut [u32], r: &mut [u32]);
+
+/// An implementation of the batched block kernel.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// Bitsliced S-boxes with no table lookups, 32 to 256 blocks at a time; available everywhere
+    /// and used for every block, including single-block calls.
+    ConstantTime,
+    /// Portable Rust, vectorized only as far as the compiler manages for the target CPU.
+    Portable,
+    /// AVX2 with 8 blocks per vector.
+    Avx2,
+    /// AVX-512F with 16 blocks per vector.
+    Avx512,
+}
+
+/// The cached result of [`Backend::selected`]; `u8::MAX` until detection has run.
+static SELECTED: AtomicU8 = AtomicU8::new(u8::MAX);
+
+impl Backend {
+    /// Every backend, in order of preference from lowest to highest.
+    pub const ALL: [Backend; 4] = [
+        Backend::ConstantTime,
+        Backend::Portable,
+        Backend::Avx2,
+        Backend::Avx512,
+    ];
+
+    /// The best backend available on this CPU, detected on first use; never
+    /// [`Backend::ConstantTime`].
+    ///
+    /// This is what instances use unless their [`IceConfig`](crate::IceConfig) says otherwise.
+    pub fn selected() -> Backend {
+        match SELECTED.load(Ordering::Relaxed) {
+            u8::MAX => {
+                let backend = Self::ALL
+                    .into_iter()
+                    .rev()
+                    .find(|backend| backend.is_available())
+                    .unwrap_or(Backend::Portable);
+                SELECTED.store(backend as u8, Ordering::Relaxed);
+                backend
+            }
+            n => Self::ALL[n as usize],
+        }
+    }
+
+    /// Returns `true` if this CPU can run the backend.
+    pub fn is_available(self) -> bool {
+        match self {
+            Backend::ConstantTime | Backend::Portable => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx2 => x86::has_avx2(),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx512 => x86::has_avx512(),
+            #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+            Backend::Avx2 | Backend::Avx512 => false,
+        }
+    }
+
+    /// A short lowercase name, such as `"avx2"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::ConstantTime => "constant-time",
+            Backend::Portable => "portable",
+            Backend::Avx2 => "avx2",
+            Backend::Avx512 => "avx512",
+        }
+    }
+
+    /// The explicit kernel for batches of `blocks`, or `None` where the generic kernel is used:
+    /// for the portable backend and for the batch sizes where it is at least as fast.
+    ///
+    /// The gather kernels win at every level from 64 blocks, but `cargo bench -- backends::batch`
+    /// has the generic kernel ahead at 16 blocks and level with AVX-512 at 32.
+    #[inline(always)]
+    pub(crate) fn kernel(self, blocks: usize) -> Option<Kernel> {
+        match self {
+            Backend::ConstantTime => Some(bitsliced::feistel_bitsliced),
+            Backend::Portable => None,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx2 if blocks == 8 || blocks >= 32 => Some(x86::feistel_avx2),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx512 if blocks >= 64 => Some(x86::feistel_avx512),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
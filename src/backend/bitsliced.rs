@@ -0,0 +1,281 @@
+//! A kernel that evaluates the S-boxes arithmetically instead of looking them up.
+//!
+//! Each S-box entry is `ice_perm32(gf_exp7(col ^ xor, modulus) << shift)` for one of four rows,
+//! so the S-boxes need no tables at all: the kernel transposes 32, 64 or 256 blocks so that each
+//! word holds one bit position of every block, computes `x^7` in GF(2^8) for all four rows with
+//! AND and XOR, selects the row with masks and places the output bits where `ice_perm32` would.
+//! No memory access depends on the key or the data, and every lane costs the same.
+
+use core::array;
+use core::ops::{BitAnd, BitXor, BitXorAssign, Not};
+
+use crate::icefast::{ICE_SMOD, ICE_SXOR, IceSubkey, ice_perm32};
+
+/// One bit position of `32 * N` blocks: bit `j` of word `w` belongs to block `32 * w + j`.
+#[derive(Clone, Copy)]
+struct Slice<const N: usize>([u32; N]);
+
+impl<const N: usize> Slice<N> {
+    const ZERO: Self = Self([0; N]);
+
+    /// Bit 0 of `bit` in every block, which makes key bits branch-free masks.
+    #[inline(always)]
+    fn splat(bit: u32) -> Self {
+        Self([0u32.wrapping_sub(bit & 1); N])
+    }
+}
+
+impl<const N: usize> BitAnd for Slice<N> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitand(self, rhs: Self) -> Self {
+        Self(array::from_fn(|w| self.0[w] & rhs.0[w]))
+    }
+}
+
+impl<const N: usize> BitXor for Slice<N> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitxor(self, rhs: Self) -> Self {
+        Self(array::from_fn(|w| self.0[w] ^ rhs.0[w]))
+    }
+}
+
+impl<const N: usize> BitXorAssign for Slice<N> {
+    #[inline(always)]
+    fn bitxor_assign(&mut self, rhs: Self) {
+        *self = *self ^ rhs;
+    }
+}
+
+impl<const N: usize> Not for Slice<N> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn not(self) -> Self {
+        Self(self.0.map(|word| !word))
+    }
+}
+
+/// A GF(2^8) element, one [`Slice`] per bit from least significant.
+type Byte<const N: usize> = [Slice<N>; 8];
+
+/// Where S-box `k`'s output bit `b` lands: `ice_perm32` applied to bit `b` of its output byte.
+const SBOX_OUT: [[usize; 8]; 4] = {
+    let mut out = [[0; 8]; 4];
+    let mut k = 0;
+    while k < 4 {
+        let mut b = 0;
+        while b < 8 {
+            out[k][b] = ice_perm32(1 << (8 * (3 - k) + b)).trailing_zeros() as usize;
+            b += 1;
+        }
+        k += 1;
+    }
+    out
+};
+
+/// Runs every round of `keysched` over the blocks whose halves are `l` and `r`.
+pub(super) fn feistel_bitsliced(
+    keysched: &[IceSubkey],
+    decrypt: bool,
+    l: &mut [u32],
+    r: &mut [u32],
+) {
+    let mut l_256 = l.chunks_exact_mut(256);
+    let mut r_256 = r.chunks_exact_mut(256);
+    for (l, r) in (&mut l_256).zip(&mut r_256) {
+        rounds::<8>(keysched, decrypt, l, r);
+    }
+
+    let mut l_64 = l_256.into_remainder().chunks_exact_mut(64);
+    let mut r_64 = r_256.into_remainder().chunks_exact_mut(64);
+    for (l, r) in (&mut l_64).zip(&mut r_64) {
+        rounds::<2>(keysched, decrypt, l, r);
+    }
+
+    let mut l_32 = l_64.into_remainder().chunks_exact_mut(32);
+    let mut r_32 = r_64.into_remainder().chunks_exact_mut(32);
+    for (l, r) in (&mut l_32).zip(&mut r_32) {
+        rounds::<1>(keysched, decrypt, l, r);
+    }
+
+    // Pad the last few blocks to a full lane rather than fall back to the tables.
+    let (l, r) = (l_32.into_remainder(), r_32.into_remainder());
+    if !l.is_empty() {
+        let (mut l_pad, mut r_pad) = ([0u32; 32], [0u32; 32]);
+        l_pad[..l.len()].copy_from_slice(l);
+        r_pad[..r.len()].copy_from_slice(r);
+        rounds::<1>(keysched, decrypt, &mut l_pad, &mut r_pad);
+        l.copy_from_slice(&l_pad[..l.len()]);
+        r.copy_from_slice(&r_pad[..r.len()]);
+    }
+}
+
+/// Runs every round over `32 * N` blocks.
+#[inline(always)]
+fn rounds<const N: usize>(keysched: &[IceSubkey], decrypt: bool, l: &mut [u32], r: &mut [u32]) {
+    let mut ls = load::<N>(l);
+    let mut rs = load::<N>(r);
+
+    let round = |x: &mut [Slice<N>; 32], y: &[Slice<N>; 32], sk: &IceSubkey| {
+        for (x, f) in x.iter_mut().zip(f(y, sk)) {
+            *x ^= f;
+        }
+    };
+    if decrypt {
+        for pair in keysched.rchunks_exact(2) {
+            round(&mut ls, &rs, &pair[1]);
+            round(&mut rs, &ls, &pair[0]);
+        }
+    } else {
+        for pair in keysched.chunks_exact(2) {
+            round(&mut ls, &rs, &pair[0]);
+            round(&mut rs, &ls, &pair[1]);
+        }
+    }
+
+    store(&ls, l);
+    store(&rs, r);
+}
+
+/// The round function; see `ice_f_batch`.
+#[inline(always)]
+fn f<const N: usize>(p: &[Slice<N>; 32], sk: &IceSubkey) -> [Slice<N>; 32] {
+    let [sk0, sk1, sk2] = sk.val();
+
+    // The expansion of `ice_f_batch` is a fixed selection of bits.
+    let tr = |i: usize| if i < 10 { p[i] } else { p[i - 2] };
+    let tl = |i: usize| if i < 10 { p[16 + i] } else { p[(i + 14) % 32] };
+
+    let mut al = [Slice::ZERO; 20];
+    let mut ar = [Slice::ZERO; 20];
+    for i in 0..20 {
+        let (tl, tr) = (tl(i), tr(i));
+        let al_base = Slice::splat(sk2 >> i) & (tl ^ tr);
+        al[i] = al_base ^ tl ^ Slice::splat(sk0 >> i);
+        ar[i] = al_base ^ tr ^ Slice::splat(sk1 >> i);
+    }
+
+    let mut out = [Slice::ZERO; 32];
+    let inputs = [&al[10..], &al[..10], &ar[10..], &ar[..10]];
+    for (k, input) in inputs.into_iter().enumerate() {
+        let y = sbox(k, input.try_into().unwrap());
+        for (b, bit) in y.into_iter().enumerate() {
+            out[SBOX_OUT[k][b]] = bit;
+        }
+    }
+    out
+}
+
+/// S-box `k` before the permutation: input bits 0 and 9 pick the row and bits 1 to 8 are the
+/// column, as in `build_sboxes`.
+#[inline(always)]
+fn sbox<const N: usize>(k: usize, x: &[Slice<N>; 10]) -> Byte<N> {
+    let col: Byte<N> = array::from_fn(|i| x[i + 1]);
+    let mut out = [Slice::ZERO; 8];
+    for row in 0..4 {
+        let xor = ICE_SXOR[k][row];
+        let b = array::from_fn(|i| if xor >> i & 1 != 0 { !col[i] } else { col[i] });
+        let y = gf_exp7(&b, ICE_SMOD[k][row]);
+
+        let r0 = if row & 1 != 0 { x[0] } else { !x[0] };
+        let r1 = if row & 2 != 0 { x[9] } else { !x[9] };
+        let select = r0 & r1;
+        for (out, y) in out.iter_mut().zip(y) {
+            // The rows are exclusive, so XOR merges them.
+            *out ^= select & y;
+        }
+    }
+    out
+}
+
+/// `b^7` modulo `m`, as `b * (b * b^2)^2`; zero maps to zero without a special case.
+#[inline(always)]
+fn gf_exp7<const N: usize>(b: &Byte<N>, m: u32) -> Byte<N> {
+    let b3 = gf_mult(&gf_square(b, m), b, m);
+    gf_mult(&gf_square(&b3, m), b, m)
+}
+
+#[inline(always)]
+fn gf_mult<const N: usize>(a: &Byte<N>, b: &Byte<N>, m: u32) -> Byte<N> {
+    let mut p = [Slice::ZERO; 15];
+    for i in 0..8 {
+        for j in 0..8 {
+            p[i + j] ^= a[i] & b[j];
+        }
+    }
+    gf_reduce(p, m)
+}
+
+/// Squaring is linear in GF(2^8): the bits spread to the even powers.
+#[inline(always)]
+fn gf_square<const N: usize>(a: &Byte<N>, m: u32) -> Byte<N> {
+    let mut p = [Slice::ZERO; 15];
+    for i in 0..8 {
+        p[2 * i] = a[i];
+    }
+    gf_reduce(p, m)
+}
+
+/// Reduces a product modulo `m`, folding each power from `x^14` down to `x^8` with
+/// `x^8 = m - x^8`.
+#[inline(always)]
+fn gf_reduce<const N: usize>(mut p: [Slice<N>; 15], m: u32) -> Byte<N> {
+    for d in (8..15).rev() {
+        for i in 0..8 {
+            if m >> i & 1 != 0 {
+                let high = p[d];
+                p[d - 8 + i] ^= high;
+            }
+        }
+    }
+    array::from_fn(|i| p[i])
+}
+
+/// Transposes `32 * N` halves into bit positions.
+#[inline(always)]
+fn load<const N: usize>(halves: &[u32]) -> [Slice<N>; 32] {
+    assert!(halves.len() == 32 * N);
+    let mut out = [Slice::ZERO; 32];
+    for (w, words) in halves.chunks_exact(32).enumerate() {
+        let mut matrix: [u32; 32] = words.try_into().unwrap();
+        transpose(&mut matrix);
+        for (slice, word) in out.iter_mut().zip(matrix) {
+            slice.0[w] = word;
+        }
+    }
+    out
+}
+
+/// The inverse of [`load`].
+#[inline(always)]
+fn store<const N: usize>(slices: &[Slice<N>; 32], halves: &mut [u32]) {
+    assert!(halves.len() == 32 * N);
+    for (w, words) in halves.chunks_exact_mut(32).enumerate() {
+        let mut matrix: [u32; 32] = array::from_fn(|bit| slices[bit].0[w]);
+        transpose(&mut matrix);
+        words.copy_from_slice(&matrix);
+    }
+}
+
+/// Transposes a 32x32 bit matrix in place, so bit `c` of row `k` becomes bit `k` of row `c`,
+/// by swapping ever smaller off-diagonal blocks.
+#[inline(always)]
+fn transpose(m: &mut [u32; 32]) {
+    let mut j = 16;
+    let mut mask = 0x0000_ffff_u32;
+    while j != 0 {
+        let mut k = 0;
+        while k < 32 {
+            let t = ((m[k] >> j) ^ m[k + j]) & mask;
+            m[k] ^= t << j;
+            m[k + j] ^= t;
+            k = (k + j + 1) & !j;
+        }
+        j >>= 1;
+        mask ^= mask << j;
+    }
+}
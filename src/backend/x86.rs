@@ -0,0 +1,253 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use crate::icefast::{ICE_SBOXES, IceSubkey, feistel};
+
+#[cfg(feature = "std")]
+pub(super) fn has_avx2() -> bool {
+    std::arch::is_x86_feature_detected!("avx2")
+}
+
+#[cfg(not(feature = "std"))]
+pub(super) fn has_avx2() -> bool {
+    cfg!(target_feature = "avx2")
+}
+
+#[cfg(feature = "std")]
+pub(super) fn has_avx512() -> bool {
+    std::arch::is_x86_feature_detected!("avx512f") && has_avx2()
+}
+
+#[cfg(not(feature = "std"))]
+pub(super) fn has_avx512() -> bool {
+    cfg!(target_feature = "avx512f") && has_avx2()
+}
+
+/// Vectors processed together, so the gathers of one overlap the arithmetic of the others.
+const INTERLEAVE: usize = 4;
+
+/// # Safety
+/// The CPU must support AVX2.
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn feistel_avx2(
+    keysched: &[IceSubkey],
+    decrypt: bool,
+    l: &mut [u32],
+    r: &mut [u32],
+) {
+    let mut l_groups = l.chunks_exact_mut(8 * INTERLEAVE);
+    let mut r_groups = r.chunks_exact_mut(8 * INTERLEAVE);
+    for (l, r) in (&mut l_groups).zip(&mut r_groups) {
+        rounds_avx2::<INTERLEAVE>(keysched, decrypt, l, r);
+    }
+
+    let mut l_blocks = l_groups.into_remainder().chunks_exact_mut(8);
+    let mut r_blocks = r_groups.into_remainder().chunks_exact_mut(8);
+    for (l, r) in (&mut l_blocks).zip(&mut r_blocks) {
+        rounds_avx2::<1>(keysched, decrypt, l, r);
+    }
+    feistel_rest(
+        keysched,
+        decrypt,
+        l_blocks.into_remainder(),
+        r_blocks.into_remainder(),
+    );
+}
+
+/// Runs every round over `N` vectors of 8 blocks.
+#[target_feature(enable = "avx2")]
+fn rounds_avx2<const N: usize>(
+    keysched: &[IceSubkey],
+    decrypt: bool,
+    l: &mut [u32],
+    r: &mut [u32],
+) {
+    assert!(l.len() == 8 * N && r.len() == 8 * N);
+    // SAFETY: each vector covers 8 of the `8 * N` `u32`s, and unaligned access is allowed.
+    let mut lv: [__m256i; N] =
+        core::array::from_fn(|i| unsafe { _mm256_loadu_si256(l[8 * i..].as_ptr().cast()) });
+    let mut rv: [__m256i; N] =
+        core::array::from_fn(|i| unsafe { _mm256_loadu_si256(r[8 * i..].as_ptr().cast()) });
+
+    let round = |lv: &mut [__m256i; N], rv: &[__m256i; N], sk: &IceSubkey| {
+        let keys = sk.val().map(|v| _mm256_set1_epi32(v as i32));
+        for (l, r) in lv.iter_mut().zip(rv) {
+            *l = _mm256_xor_si256(*l, f_avx2(*r, &keys));
+        }
+    };
+    if decrypt {
+        for pair in keysched.rchunks_exact(2) {
+            round(&mut lv, &rv, &pair[1]);
+            round(&mut rv, &lv, &pair[0]);
+        }
+    } else {
+        for pair in keysched.chunks_exact(2) {
+            round(&mut lv, &rv, &pair[0]);
+            round(&mut rv, &lv, &pair[1]);
+        }
+    }
+
+    for i in 0..N {
+        // SAFETY: as for the loads.
+        unsafe {
+            _mm256_storeu_si256(l[8 * i..].as_mut_ptr().cast(), lv[i]);
+            _mm256_storeu_si256(r[8 * i..].as_mut_ptr().cast(), rv[i]);
+        }
+    }
+}
+
+/// The round function for 8 half-blocks; see `ice_f_batch`.
+#[target_feature(enable = "avx2")]
+#[inline]
+fn f_avx2(p: __m256i, &[sk0, sk1, sk2]: &[__m256i; 3]) -> __m256i {
+    let low = _mm256_set1_epi32(0x3ff);
+    let high = _mm256_set1_epi32(0xffc00);
+
+    let tr = _mm256_or_si256(
+        _mm256_and_si256(p, low),
+        _mm256_and_si256(_mm256_slli_epi32::<2>(p), high),
+    );
+    let rotated = _mm256_or_si256(_mm256_slli_epi32::<18>(p), _mm256_srli_epi32::<14>(p));
+    let tl = _mm256_or_si256(
+        _mm256_and_si256(_mm256_srli_epi32::<16>(p), low),
+        _mm256_and_si256(rotated, high),
+    );
+
+    let al_base = _mm256_and_si256(sk2, _mm256_xor_si256(tl, tr));
+    let al = _mm256_xor_si256(_mm256_xor_si256(al_base, tl), sk0);
+    let ar = _mm256_xor_si256(_mm256_xor_si256(al_base, tr), sk1);
+
+    let s = ICE_SBOXES.as_ptr().cast::<i32>();
+    // SAFETY: every index is masked to 10 bits, inside its 1024-entry S-box.
+    unsafe {
+        let s0 = _mm256_i32gather_epi32::<4>(s, _mm256_and_si256(_mm256_srli_epi32::<10>(al), low));
+        let s1 = _mm256_i32gather_epi32::<4>(s.add(1024), _mm256_and_si256(al, low));
+        let s2 = _mm256_i32gather_epi32::<4>(
+            s.add(2048),
+            _mm256_and_si256(_mm256_srli_epi32::<10>(ar), low),
+        );
+        let s3 = _mm256_i32gather_epi32::<4>(s.add(3072), _mm256_and_si256(ar, low));
+        _mm256_or_si256(_mm256_or_si256(s0, s1), _mm256_or_si256(s2, s3))
+    }
+}
+
+/// # Safety
+/// The CPU must support AVX-512F and AVX2.
+#[target_feature(enable = "avx512f,avx2")]
+pub(super) unsafe fn feistel_avx512(
+    keysched: &[IceSubkey],
+    decrypt: bool,
+    l: &mut [u32],
+    r: &mut [u32],
+) {
+    let mut l_groups = l.chunks_exact_mut(16 * INTERLEAVE);
+    let mut r_groups = r.chunks_exact_mut(16 * INTERLEAVE);
+    for (l, r) in (&mut l_groups).zip(&mut r_groups) {
+        rounds_avx512::<INTERLEAVE>(keysched, decrypt, l, r);
+    }
+
+    let mut l_blocks = l_groups.into_remainder().chunks_exact_mut(16);
+    let mut r_blocks = r_groups.into_remainder().chunks_exact_mut(16);
+    for (l, r) in (&mut l_blocks).zip(&mut r_blocks) {
+        rounds_avx512::<1>(keysched, decrypt, l, r);
+    }
+    // SAFETY: the caller guarantees AVX2.
+    unsafe {
+        feistel_avx2(
+            keysched,
+            decrypt,
+            l_blocks.into_remainder(),
+            r_blocks.into_remainder(),
+        )
+    };
+}
+
+/// Runs every round over `N` vectors of 16 blocks.
+#[target_feature(enable = "avx512f")]
+fn rounds_avx512<const N: usize>(
+    keysched: &[IceSubkey],
+    decrypt: bool,
+    l: &mut [u32],
+    r: &mut [u32],
+) {
+    assert!(l.len() == 16 * N && r.len() == 16 * N);
+    // SAFETY: each vector covers 16 of the `16 * N` `u32`s, and unaligned access is allowed.
+    let mut lv: [__m512i; N] =
+        core::array::from_fn(|i| unsafe { _mm512_loadu_si512(l[16 * i..].as_ptr().cast()) });
+    let mut rv: [__m512i; N] =
+        core::array::from_fn(|i| unsafe { _mm512_loadu_si512(r[16 * i..].as_ptr().cast()) });
+
+    let round = |lv: &mut [__m512i; N], rv: &[__m512i; N], sk: &IceSubkey| {
+        let keys = sk.val().map(|v| _mm512_set1_epi32(v as i32));
+        for (l, r) in lv.iter_mut().zip(rv) {
+            *l = _mm512_xor_si512(*l, f_avx512(*r, &keys));
+        }
+    };
+    if decrypt {
+        for pair in keysched.rchunks_exact(2) {
+            round(&mut lv, &rv, &pair[1]);
+            round(&mut rv, &lv, &pair[0]);
+        }
+    } else {
+        for pair in keysched.chunks_exact(2) {
+            round(&mut lv, &rv, &pair[0]);
+            round(&mut rv, &lv, &pair[1]);
+        }
+    }
+
+    for i in 0..N {
+        // SAFETY: as for the loads.
+        unsafe {
+            _mm512_storeu_si512(l[16 * i..].as_mut_ptr().cast(), lv[i]);
+            _mm512_storeu_si512(r[16 * i..].as_mut_ptr().cast(), rv[i]);
+        }
+    }
+}
+
+/// The round function for 16 half-blocks; see `ice_f_batch`.
+#[target_feature(enable = "avx512f")]
+#[inline]
+fn f_avx512(p: __m512i, &[sk0, sk1, sk2]: &[__m512i; 3]) -> __m512i {
+    let low = _mm512_set1_epi32(0x3ff);
+    let high = _mm512_set1_epi32(0xffc00);
+
+    let tr = _mm512_or_si512(
+        _mm512_and_si512(p, low),
+        _mm512_and_si512(_mm512_slli_epi32::<2>(p), high),
+    );
+    let tl = _mm512_or_si512(
+        _mm512_and_si512(_mm512_srli_epi32::<16>(p), low),
+        _mm512_and_si512(_mm512_rol_epi32::<18>(p), high),
+    );
+
+    let al_base = _mm512_and_si512(sk2, _mm512_xor_si512(tl, tr));
+    let al = _mm512_xor_si512(_mm512_xor_si512(al_base, tl), sk0);
+    let ar = _mm512_xor_si512(_mm512_xor_si512(al_base, tr), sk1);
+
+    let s = ICE_SBOXES.as_ptr().cast::<i32>();
+    // SAFETY: every index is masked to 10 bits, inside its 1024-entry S-box.
+    unsafe {
+        let s0 = _mm512_i32gather_epi32::<4>(_mm512_and_si512(_mm512_srli_epi32::<10>(al), low), s);
+        let s1 = _mm512_i32gather_epi32::<4>(_mm512_and_si512(al, low), s.add(1024));
+        let s2 = _mm512_i32gather_epi32::<4>(
+            _mm512_and_si512(_mm512_srli_epi32::<10>(ar), low),
+            s.add(2048),
+        );
+        let s3 = _mm512_i32gather_epi32::<4>(_mm512_and_si512(ar, low), s.add(3072));
+        _mm512_or_si512(_mm512_or_si512(s0, s1), _mm512_or_si512(s2, s3))
+    }
+}
+
+/// Finishes blocks that do not fill a vector with the portable kernel, one at a time.
+fn feistel_rest(keysched: &[IceSubkey], decrypt: bool, l: &mut [u32], r: &mut [u32]) {
+    for (l, r) in l.iter_mut().zip(r) {
+        let (l, r) = (core::array::from_mut(l), core::array::from_mut(r));
+        if decrypt {
+            feistel::<1, true>(keysched, l, r);
+        } else {
+            feistel::<1, false>(keysched, l, r);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! C ABI matching Matthew Kwan's `ice.h`, behind the `capi` feature.
+//!
+//! The seven `ice_key_*` functions of the reference library are exported unchanged, so C and
+//! C++ programs written against `ice.h` can link icefast instead of `ice.c`. The `_buffer`
+//! extensions process whole buffers through the auto-dispatched path. `include/icefast.h`
+//! declares all of them, compatibly with `ice.h`, and is generated from this file with
+//! `cbindgen`.
+//!
+//! `cargo build --release -p icefast-capi` builds both `libicefast.so` (or the platform's
+//! equivalent) and `libicefast.a` from the `capi` directory of the repository.
+
+use std::boxed::Box;
+use std::ffi::{c_int, c_uchar};
+
+use crate::icefast::{BLOCK_SIZE, IceKernel};
+use crate::{Ice, IceLevel};
+
+/// Creates an ICE key of level `n`, where `n < 1` selects Thin-ICE.
+///
+/// The key must be set with `ice_key_set` before use. Unlike `ice.c`, which accepts any `n`,
+/// this returns `NULL` for levels above 8, so callers must check the result.
+#[unsafe(no_mangle)]
+pub extern "C" fn ice_key_create(n: c_int) -> *mut Ice {
+    let level = n.max(0) as usize;
+    if level > IceLevel::MAX {
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(Ice::new(level, &[0; IceLevel::MAX * 8])))
+}
+
+/// Zeroes and frees an ICE key. `NULL` is ignored.
+///
+/// # Safety
+/// `ik` must be `NULL` or a key from `ice_key_create` that has not been destroyed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ice_key_destroy(ik: *mut Ice) {
+    if ik.is_null() {
+        return;
+    }
+    // SAFETY: the caller guarantees `ik` came from `Box::into_raw` in `ice_key_create`.
+    let mut ice = unsafe { Box::from_raw(ik) };
+    ice.wipe();
+}
+
+/// Sets the key of `ik` from `ice_key_key_size(ik)` bytes at `k`.
+///
+/// # Safety
+/// `ik` must be a live key and `k` must point to `ice_key_key_size(ik)` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ice_key_set(ik: *mut Ice, k: *const c_uchar) {
+    // SAFETY: guaranteed by the caller.
+    let ice = unsafe { &mut *ik };
+    let key = unsafe { std::slice::from_raw_parts(k, ice.key_size()) };
+    ice.set_key(key)
+        .expect("the key is exactly key_size() bytes");
+}
+
+/// Encrypts the 8-byte block at `ptxt` into `ctxt`, which may be the same buffer.
+///
+/// # Safety
+/// `ik` must be a live key, `ptxt` must point to 8 readable bytes and `ctxt` to 8 writable
+/// bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ice_key_encrypt(ik: *const Ice, ptxt: *const c_uchar, ctxt: *mut c_uchar) {
+    // SAFETY: guaranteed by the caller; the block is read before it is written.
+    unsafe {
+        let block = (*ik).encrypt_block(ptxt.cast::<[u8; BLOCK_SIZE]>().read_unaligned());
+        ctxt.cast::<[u8; BLOCK_SIZE]>().write_unaligned(block);
+    }
+}
+
+/// Decrypts the 8-byte block at `ctxt` into `ptxt`, which may be the same buffer.
+///
+/// # Safety
+/// `ik` must be a live key, `ctxt` must point to 8 readable bytes and `ptxt` to 8 writable
+/// bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ice_key_decrypt(ik: *const Ice, ctxt: *const c_uchar, ptxt: *mut c_uchar) {
+    // SAFETY: guaranteed by the caller; the block is read before it is written.
+    unsafe {
+        let block = (*ik).decrypt_block(ctxt.cast::<[u8; BLOCK_SIZE]>().read_unaligned());
+        ptxt.cast::<[u8; BLOCK_SIZE]>().write_unaligned(block);
+    }
+}
+
+/// Returns the key size of `ik` in bytes.
+///
+/// # Safety
+/// `ik` must be a live key.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ice_key_key_size(ik: *const Ice) -> c_int {
+    // SAFETY: guaranteed by the caller.
+    unsafe { (*ik).key_size() as c_int }
+}
+
+/// Returns the block size of `ik` in bytes, which is always 8.
+///
+/// # Safety
+/// `ik` must be a live key.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ice_key_block_size(ik: *const Ice) -> c_int {
+    // SAFETY: guaranteed by the caller.
+    unsafe { (*ik).block_size() as c_int }
+}
+
+/// Encrypts `len` bytes at `data` in-place, serially or in parallel depending on `len`.
+///
+/// Returns 0 on success, or -1 without touching `data` if `len` is not a multiple of 8.
+///
+/// # Safety
+/// `ik` must be a live key and `data` must point to `len` writable bytes, or may be `NULL` if
+/// `len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ice_key_encrypt_buffer(
+    ik: *const Ice,
+    data: *mut c_uchar,
+    len: usize,
+) -> c_int {
+    // SAFETY: guaranteed by the caller.
+    unsafe { process_buffer::<false>(ik, data, len) }
+}
+
+/// Decrypts `len` bytes at `data` in-place, serially or in parallel depending on `len`.
+///
+/// Returns 0 on success, or -1 without touching `data` if `len` is not a multiple of 8.
+///
+/// # Safety
+/// `ik` must be a live key and `data` must point to `len` writable bytes, or may be `NULL` if
+/// `len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ice_key_decrypt_buffer(
+    ik: *const Ice,
+    data: *mut c_uchar,
+    len: usize,
+) -> c_int {
+    // SAFETY: guaranteed by the caller.
+    unsafe { process_buffer::<true>(ik, data, len) }
+}
+
+unsafe fn process_buffer<const DECRYPT: bool>(
+    ik: *const Ice,
+    data: *mut c_uchar,
+    len: usize,
+) -> c_int {
+    if !len.is_multiple_of(BLOCK_SIZE) {
+        return -1;
+    }
+    if len > 0 {
+        // SAFETY: guaranteed by the caller.
+        let data = unsafe { std::slice::from_raw_parts_mut(data, len) };
+        unsafe { (*ik).dispatch_auto::<DECRYPT>(data) };
+    }
+    0
+}
@@ -0,0 +1,297 @@
+//! Per-instance dispatch tuning and the [`Ice::builder`] entry point.
+//!
+//! The defaults were tuned on a Ryzen 5700G. Deployments can override them without recompiling
+//! through these environment variables, read once on first use (requires the `std` feature):
+//!
+//! * `ICEFAST_PARALLEL_THRESHOLD`: input length in bytes from which the `_auto` methods go
+//!   parallel.
+//! * `ICEFAST_MAX_SERIAL_BATCH_EXP`: largest serial batch, as a power of two of blocks (0 to 9).
+//! * `ICEFAST_MAX_PARALLEL_BATCH_EXP`: largest parallel batch, as a power of two of blocks
+//!   (0 to 8).
+//!
+//! Values that do not parse or are out of range are ignored. Settings made on an
+//! [`IceBuilder`] take precedence over the environment.
+
+#[cfg(feature = "parallel")]
+use alloc::sync::Arc;
+#[cfg(feature = "parallel")]
+use rayon::ThreadPool;
+
+use crate::backend::Backend;
+use crate::{Ice, IceError, IceFixed};
+
+/// Default input length from which the `_auto` methods process in parallel.
+pub(crate) const DEFAULT_PARALLEL_THRESHOLD: usize = 32_768;
+
+/// Dispatch tuning carried by each [`Ice`] or [`IceFixed`] instance.
+///
+/// Instances built without an [`IceBuilder`] share the process-wide default rather than holding
+/// a copy.
+#[derive(Clone, Debug)]
+pub struct IceConfig {
+    pub(crate) parallel_threshold: usize,
+    pub(crate) max_serial_batch_exp: u32,
+    pub(crate) max_parallel_batch_exp: u32,
+    backend: Option<Backend>,
+    #[cfg(feature = "parallel")]
+    thread_pool: Option<Arc<ThreadPool>>,
+}
+
+impl IceConfig {
+    /// Largest supported serial batch exponent; the serial dispatcher is specialized up to
+    /// 512 blocks.
+    pub const MAX_SERIAL_BATCH_EXP: u32 = 9;
+
+    /// Largest supported parallel batch exponent; the parallel dispatcher is specialized up to
+    /// 256 blocks.
+    pub const MAX_PARALLEL_BATCH_EXP: u32 = 8;
+
+    /// The built-in defaults, ignoring the environment.
+    pub const fn new() -> Self {
+        Self {
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+            max_serial_batch_exp: Self::MAX_SERIAL_BATCH_EXP,
+            max_parallel_batch_exp: Self::MAX_PARALLEL_BATCH_EXP,
+            backend: None,
+            #[cfg(feature = "parallel")]
+            thread_pool: None,
+        }
+    }
+
+    /// Input length in bytes from which the `_auto` methods process in parallel.
+    pub fn parallel_threshold(&self) -> usize {
+        self.parallel_threshold
+    }
+
+    /// Largest serial batch, as a power of two of 8-byte blocks.
+    pub fn max_serial_batch_exp(&self) -> u32 {
+        self.max_serial_batch_exp
+    }
+
+    /// Largest parallel batch, as a power of two of 8-byte blocks.
+    pub fn max_parallel_batch_exp(&self) -> u32 {
+        self.max_parallel_batch_exp
+    }
+
+    /// The block kernel backend; [`Backend::selected`] unless overridden.
+    #[inline]
+    pub fn backend(&self) -> Backend {
+        self.backend.unwrap_or_else(Backend::selected)
+    }
+
+    /// The pool parallel work runs on, or `None` for rayon's global pool.
+    #[cfg(feature = "parallel")]
+    pub fn thread_pool(&self) -> Option<&Arc<ThreadPool>> {
+        self.thread_pool.as_ref()
+    }
+
+    /// The process-wide default: the built-in defaults with any environment overrides applied.
+    #[cfg(feature = "std")]
+    pub(crate) fn global() -> &'static IceConfig {
+        static GLOBAL: std::sync::OnceLock<IceConfig> = std::sync::OnceLock::new();
+        GLOBAL.get_or_init(|| {
+            let mut config = IceConfig::new();
+            config.apply_env();
+            config
+        })
+    }
+
+    /// The process-wide default; without `std` there is no environment to read.
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn global() -> &'static IceConfig {
+        static GLOBAL: IceConfig = IceConfig::new();
+        &GLOBAL
+    }
+
+    #[cfg(feature = "std")]
+    fn apply_env(&mut self) {
+        fn var<T: core::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok()?.trim().parse().ok()
+        }
+
+        if let Some(threshold) = var("ICEFAST_PARALLEL_THRESHOLD") {
+            self.parallel_threshold = threshold;
+        }
+        if let Some(exp) =
+            var("ICEFAST_MAX_SERIAL_BATCH_EXP").filter(|&exp| exp <= Self::MAX_SERIAL_BATCH_EXP)
+        {
+            self.max_serial_batch_exp = exp;
+        }
+        if let Some(exp) =
+            var("ICEFAST_MAX_PARALLEL_BATCH_EXP").filter(|&exp| exp <= Self::MAX_PARALLEL_BATCH_EXP)
+        {
+            self.max_parallel_batch_exp = exp;
+        }
+    }
+
+    fn validate(&self) -> Result<(), IceError> {
+        let check = |exp, max| {
+            if exp > max {
+                Err(IceError::UnsupportedBatchExponent { max, actual: exp })
+            } else {
+                Ok(())
+            }
+        };
+        check(self.max_serial_batch_exp, Self::MAX_SERIAL_BATCH_EXP)?;
+        check(self.max_parallel_batch_exp, Self::MAX_PARALLEL_BATCH_EXP)?;
+        match self.backend {
+            Some(backend) if !backend.is_available() => Err(IceError::UnsupportedBackend(backend)),
+            _ => Ok(()),
+        }
+    }
+
+    /// The serial/parallel decision used by the `_auto` entry points.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn auto_parallel(&self, len: usize) -> bool {
+        let threads = match &self.thread_pool {
+            Some(pool) => pool.current_num_threads(),
+            None => rayon::current_num_threads(),
+        };
+        len >= self.parallel_threshold && threads >= 2
+    }
+
+    /// Without the `parallel` feature the `_auto` entry points are always serial.
+    #[cfg(not(feature = "parallel"))]
+    pub(crate) fn auto_parallel(&self, _len: usize) -> bool {
+        false
+    }
+
+    /// Runs `op` on the configured pool, or in the current context for the global pool.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn run_par<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match &self.thread_pool {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+}
+
+impl Default for IceConfig {
+    /// The built-in defaults with any `ICEFAST_*` environment overrides applied.
+    fn default() -> Self {
+        Self::global().clone()
+    }
+}
+
+/// Builds an [`Ice`] or [`IceFixed`] instance with its own dispatch tuning; see
+/// [`Ice::builder`].
+///
+/// # Examples
+///
+/// ```rust
+/// use icefast::Ice;
+///
+/// let ice = Ice::builder()
+///     .level(1)
+///     .key(&[0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00])
+///     .parallel_threshold(1 << 20)
+///     .max_serial_batch_exp(6)
+///     .build()
+///     .unwrap();
+///
+/// let mut data = vec![0u8; 4096];
+/// ice.encrypt_auto(&mut data);
+/// ```
+#[derive(Clone, Debug)]
+pub struct IceBuilder<'a> {
+    level: usize,
+    key: &'a [u8],
+    config: IceConfig,
+}
+
+impl<'a> IceBuilder<'a> {
+    pub(crate) fn new() -> Self {
+        Self {
+            level: 0,
+            key: &[],
+            config: IceConfig::default(),
+        }
+    }
+
+    /// The ICE level, in the range [0, 8]; defaults to Thin-ICE (level 0).
+    pub fn level(mut self, level: usize) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// The key, which must be 8 bytes per level; any extra bytes are ignored.
+    pub fn key(mut self, key: &'a [u8]) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Replaces every tuning setting with `config`.
+    pub fn config(mut self, config: IceConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Input length in bytes from which the `_auto` methods process in parallel.
+    pub fn parallel_threshold(mut self, bytes: usize) -> Self {
+        self.config.parallel_threshold = bytes;
+        self
+    }
+
+    /// Largest serial batch, as a power of two of 8-byte blocks, at most
+    /// [`IceConfig::MAX_SERIAL_BATCH_EXP`].
+    pub fn max_serial_batch_exp(mut self, exp: u32) -> Self {
+        self.config.max_serial_batch_exp = exp;
+        self
+    }
+
+    /// Largest parallel batch, as a power of two of 8-byte blocks, at most
+    /// [`IceConfig::MAX_PARALLEL_BATCH_EXP`].
+    pub fn max_parallel_batch_exp(mut self, exp: u32) -> Self {
+        self.config.max_parallel_batch_exp = exp;
+        self
+    }
+
+    /// Forces the block kernel backend instead of the best one detected for this CPU.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.config.backend = Some(backend);
+        self
+    }
+
+    /// Runs the instance's parallel work on `pool` instead of rayon's global pool.
+    ///
+    /// The `_auto` methods also use its thread count for their serial/parallel decision.
+    #[cfg(feature = "parallel")]
+    pub fn thread_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.config.thread_pool = Some(pool);
+        self
+    }
+
+    /// Creates the instance.
+    ///
+    /// # Errors
+    /// Returns [`IceError::UnsupportedLevel`], [`IceError::KeyTooShort`] (including when no key
+    /// was given), [`IceError::UnsupportedBatchExponent`] or [`IceError::UnsupportedBackend`].
+    pub fn build(self) -> Result<Ice, IceError> {
+        self.config.validate()?;
+        let mut ice = Ice::try_new(self.level, self.key)?;
+        ice.config = Some(self.config);
+        Ok(ice)
+    }
+
+    /// Creates a fixed-level instance such as [`Ice2`](crate::Ice2), whose type sets the level;
+    /// any [`level`](Self::level) given is ignored.
+    ///
+    /// ```rust
+    /// use icefast::{Ice, Ice2};
+    ///
+    /// let ice: Ice2 = Ice::builder()
+    ///     .key(&[0x3C; 16])
+    ///     .parallel_threshold(1 << 20)
+    ///     .build_fixed()
+    ///     .unwrap();
+    /// assert_eq!(ice.config().parallel_threshold(), 1 << 20);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`IceError::KeyTooShort`], [`IceError::UnsupportedBatchExponent`] or
+    /// [`IceError::UnsupportedBackend`].
+    pub fn build_fixed<const ROUNDS: usize>(self) -> Result<IceFixed<ROUNDS>, IceError> {
+        self.config.validate()?;
+        IceFixed::with_config(self.key, self.config)
+    }
+}
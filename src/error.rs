@@ -0,0 +1,132 @@
+use core::fmt;
+
+use crate::backend::Backend;
+
+/// Errors reported by the fallible `try_` API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IceError {
+    /// The key is shorter than the level's key size.
+    KeyTooShort { expected: usize, actual: usize },
+    /// The level is outside the supported range; see [`IceLevel::MAX`](crate::IceLevel::MAX).
+    UnsupportedLevel(usize),
+    /// The input length is not a multiple of the 8-byte block size.
+    NotBlockAligned(usize),
+    /// The input length is not a multiple of the sector size.
+    NotSectorAligned { sector_size: usize, actual: usize },
+    /// The destination buffer length does not match the source length.
+    LengthMismatch { expected: usize, actual: usize },
+    /// The input is shorter than the operation's minimum length.
+    InputTooShort { min: usize, actual: usize },
+    /// A counter mode has used every counter value and would repeat its keystream.
+    CounterOverflow,
+    /// An authentication tag did not match the message.
+    TagMismatch,
+    /// A batch size exponent exceeds what the dispatcher is specialized for.
+    UnsupportedBatchExponent { max: u32, actual: u32 },
+    /// The requested kernel backend is not available on this CPU.
+    UnsupportedBackend(Backend),
+    /// The operation cannot produce a result from empty input.
+    ///
+    /// The in-place `try_encrypt`/`try_decrypt` family treats empty input as a no-op instead.
+    EmptyInput,
+}
+
+impl fmt::Display for IceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IceError::KeyTooShort { expected, actual } => {
+                write!(
+                    f,
+                    "ICE key is {actual} bytes but the level requires {expected}"
+                )
+            }
+            IceError::UnsupportedLevel(level) => {
+                write!(f, "ICE level {level} is not in the supported range [0, 8]")
+            }
+            IceError::NotBlockAligned(len) => {
+                write!(
+                    f,
+                    "input length {len} is not a multiple of the 8-byte block size"
+                )
+            }
+            IceError::NotSectorAligned {
+                sector_size,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "input length {actual} is not a multiple of the {sector_size}-byte sector size"
+                )
+            }
+            IceError::LengthMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "destination is {actual} bytes but the source is {expected}"
+                )
+            }
+            IceError::InputTooShort { min, actual } => {
+                write!(f, "input is {actual} bytes but at least {min} are required")
+            }
+            IceError::CounterOverflow => write!(f, "counter space exhausted"),
+            IceError::TagMismatch => write!(f, "authentication tag mismatch"),
+            IceError::UnsupportedBatchExponent { max, actual } => {
+                write!(f, "batch exponent {actual} exceeds the maximum of {max}")
+            }
+            IceError::UnsupportedBackend(backend) => {
+                write!(f, "the {backend} backend is not available on this CPU")
+            }
+            IceError::EmptyInput => write!(f, "input is empty"),
+        }
+    }
+}
+
+impl core::error::Error for IceError {}
+
+/// Errors reported when removing block padding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaddingError {
+    /// The input length is not a multiple of the 8-byte block size.
+    NotBlockAligned(usize),
+    /// The decrypted padding is malformed.
+    Invalid,
+}
+
+impl fmt::Display for PaddingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaddingError::NotBlockAligned(len) => {
+                write!(
+                    f,
+                    "input length {len} is not a multiple of the 8-byte block size"
+                )
+            }
+            PaddingError::Invalid => write!(f, "invalid padding"),
+        }
+    }
+}
+
+impl core::error::Error for PaddingError {}
+
+/// Errors reported when parsing a [`TuneProfile`](crate::tune::TuneProfile).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileError {
+    /// The line is not of the form `level N: key=value ...`, or has an unknown key.
+    Malformed { line: usize },
+    /// The level or a batch exponent on the line is outside the supported range.
+    OutOfRange { line: usize },
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::Malformed { line } => {
+                write!(f, "tuning profile line {line} is malformed")
+            }
+            ProfileError::OutOfRange { line } => {
+                write!(f, "tuning profile line {line} has an out-of-range value")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ProfileError {}
@@ -1,24 +1,24 @@
+#[cfg(feature = "parallel")]
+use rayon::ThreadPool;
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-const BLOCK_SIZE: usize = 8;
-const AUTO_PAR_THRESHOLD: usize = 32_768;
+use crate::backend::Kernel;
+use crate::config::{IceBuilder, IceConfig};
+use crate::error::IceError;
 
-/// Must be power of 2; match statement implementation is limited to 256
-const MAX_BLOCKS_PER_BATCH_PARALLEL: usize = 256;
-
-/// Must be power of 2; match statement implementation is limited to 512
-const MAX_BLOCKS_PER_BATCH_SERIAL: usize = 512;
+pub(crate) const BLOCK_SIZE: usize = 8;
 
 const KEYROT: [i32; 16] = [0, 1, 2, 3, 2, 1, 3, 0, 1, 3, 2, 0, 3, 1, 0, 2];
 
-const ICE_SMOD: [[u32; 4]; 4] = [
+pub(crate) const ICE_SMOD: [[u32; 4]; 4] = [
     [333, 313, 505, 369],
     [379, 375, 319, 391],
     [361, 445, 451, 397],
     [397, 425, 395, 505],
 ];
 
-const ICE_SXOR: [[u32; 4]; 4] = [
+pub(crate) const ICE_SXOR: [[u32; 4]; 4] = [
     [0x83, 0x85, 0x9b, 0xcd],
     [0xcc, 0xa7, 0xad, 0x41],
     [0x4b, 0x2e, 0xd4, 0x33],
@@ -57,7 +57,7 @@ const fn gf_exp7(b: u32, m: u32) -> u32 {
     gf_mult(b, x, m)
 }
 
-const fn ice_perm32(mut x: u32) -> u32 {
+pub(crate) const fn ice_perm32(mut x: u32) -> u32 {
     let mut res: u32 = 0;
     let mut i = 0;
     while i < 32 {
@@ -87,133 +87,827 @@ const fn build_sboxes() -> [u32; 4096] {
 }
 
 type IceSboxes = [u32; 4096];
-const ICE_SBOXES: IceSboxes = build_sboxes();
 
-#[derive(Clone, Debug)]
+/// Shared by every `Ice` instance; built once at compile time.
+pub(crate) static ICE_SBOXES: IceSboxes = build_sboxes();
+
+#[derive(Clone, Copy, Debug, Default)]
 pub struct IceSubkey {
     val: [u32; 3],
 }
 
+impl IceSubkey {
+    /// The three 20-bit words of the subkey: the two salt-XOR values and the salt permutation.
+    pub fn val(&self) -> [u32; 3] {
+        self.val
+    }
+}
+
+/// How the auto-dispatched `_with` entry points treat the trailing `len % 8` bytes of a buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Residual {
+    /// Panic on a non-aligned or empty buffer, as `encrypt_auto` does.
+    #[default]
+    Panic,
+    /// Process the whole blocks and pass the trailing bytes through unchanged, as Valve's
+    /// Source engine tools do.
+    LeavePlain,
+    /// Return [`IceError::NotBlockAligned`] without touching the buffer.
+    Error,
+    /// XOR the trailing bytes with the encryption of the last whole ciphertext block, or of an
+    /// all-zero block if there is none.
+    XorWithKeystream,
+}
+
+/// Highest ICE level the inline key schedule has capacity for.
+const MAX_LEVEL: usize = 8;
+const MAX_ROUNDS: usize = MAX_LEVEL * 16;
+
+/// An ICE level: Thin-ICE (0) or ICE-n for n in 1 to [`IceLevel::MAX`].
+///
+/// ICE-n runs 16·n rounds keyed by 8·n bytes; Thin-ICE runs 8 rounds keyed by 8 bytes. Levels
+/// are validated on construction, so every `IceLevel` is supported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IceLevel(usize);
+
+impl IceLevel {
+    /// Highest supported `n` for ICE-n.
+    pub const MAX: usize = MAX_LEVEL;
+
+    /// Thin-ICE, level 0.
+    pub const THIN: IceLevel = IceLevel(0);
+
+    /// Validate a numeric level as used by `ice_key_create`: 0 is Thin-ICE, 1 to
+    /// [`IceLevel::MAX`] is ICE-n.
+    ///
+    /// # Errors
+    /// Returns [`IceError::UnsupportedLevel`] for levels above [`IceLevel::MAX`].
+    pub const fn new(level: usize) -> Result<Self, IceError> {
+        if level <= MAX_LEVEL {
+            Ok(IceLevel(level))
+        } else {
+            Err(IceError::UnsupportedLevel(level))
+        }
+    }
+
+    /// Returns `true` for Thin-ICE.
+    pub const fn is_thin(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The numeric level, with Thin-ICE as 0.
+    pub const fn level(self) -> usize {
+        self.0
+    }
+
+    /// Key size in bytes, as `ice_key_key_size`.
+    pub const fn key_size(self) -> usize {
+        self.key_words() * 8
+    }
+
+    /// Block size in bytes, as `ice_key_block_size`.
+    pub const fn block_size(self) -> usize {
+        BLOCK_SIZE
+    }
+
+    /// Number of Feistel rounds.
+    pub const fn rounds(self) -> usize {
+        if self.is_thin() { 8 } else { self.0 * 16 }
+    }
+
+    /// Number of 8-byte key words; `ik_size` in the reference implementation.
+    const fn key_words(self) -> usize {
+        if self.is_thin() { 1 } else { self.0 }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct IceKeyStruct {
     size: usize,
     rounds: usize,
-    pub keysched: Vec<IceSubkey>,
+    keysched: [IceSubkey; MAX_ROUNDS],
+}
+
+impl IceKeyStruct {
+    /// The round subkeys of the active key schedule, in encryption order.
+    pub fn subkeys(&self) -> &[IceSubkey] {
+        &self.keysched[..self.rounds]
+    }
 }
 
 #[derive(Clone, Debug)]
 #[repr(C, align(64))]
 pub struct Ice {
-    sbox: IceSboxes,
     pub key: IceKeyStruct,
+    /// `None` until the instance is given its own tuning, so plain instances share the global
+    /// configuration instead of copying it.
+    pub(crate) config: Option<IceConfig>,
+}
+
+/// An ICE instance whose round count is fixed at compile time.
+///
+/// With the round count known the compiler can fully unroll the Feistel network. Use the
+/// [`IceThin`] and [`Ice1`] to [`Ice8`] aliases, whose constructors take exact-size keys, or
+/// [`IceBuilder::build_fixed`] for an instance with its own dispatch tuning.
+#[derive(Clone, Debug)]
+#[repr(C, align(64))]
+pub struct IceFixed<const ROUNDS: usize> {
+    keysched: [IceSubkey; ROUNDS],
+    /// As in [`Ice`], `None` means the global configuration.
+    config: Option<IceConfig>,
+}
+
+/// Thin-ICE (level 0): 8 rounds, 8-byte key.
+pub type IceThin = IceFixed<8>;
+
+/// ICE level 1: 16 rounds, 8-byte key.
+pub type Ice1 = IceFixed<16>;
+
+/// ICE level 2: 32 rounds, 16-byte key.
+pub type Ice2 = IceFixed<32>;
+
+/// ICE level 3: 48 rounds, 24-byte key.
+pub type Ice3 = IceFixed<48>;
+
+/// ICE level 4: 64 rounds, 32-byte key.
+pub type Ice4 = IceFixed<64>;
+
+/// ICE level 5: 80 rounds, 40-byte key.
+pub type Ice5 = IceFixed<80>;
+
+/// ICE level 6: 96 rounds, 48-byte key.
+pub type Ice6 = IceFixed<96>;
+
+/// ICE level 7: 112 rounds, 56-byte key.
+pub type Ice7 = IceFixed<112>;
+
+/// ICE level 8: 128 rounds, 64-byte key.
+pub type Ice8 = IceFixed<128>;
+
+impl TryFrom<usize> for IceLevel {
+    type Error = IceError;
+
+    fn try_from(level: usize) -> Result<Self, Self::Error> {
+        IceLevel::new(level)
+    }
 }
 
 impl Ice {
     /// Create a new ICE instance.
     ///
-    /// - `key` must be 8 bytes per `level`.
-    /// - `level` must be in the range [0, 2].
+    /// - `key` must be 8 bytes per `level`; any extra bytes are ignored.
+    /// - `level` must be in the range [0, 8], where 0 is Thin-ICE.
     ///
     /// It is recommended to use Level 0 (or Thin-ICE) for most use cases for performance reasons.
     pub fn new(level: usize, key: &[u8]) -> Self {
+        let level = IceLevel::new(level).expect("ICE level must be in the range [0, 8]");
+        Self::with_level(level, key)
+    }
+
+    /// Create a new ICE instance for `level`.
+    ///
+    /// # Panics
+    /// Panics if `key` is shorter than `level.key_size()`.
+    pub fn with_level(level: IceLevel, key: &[u8]) -> Self {
         assert!(
-            key.len() >= (if level < 1 { 1 } else { level }) * 8,
+            key.len() >= level.key_size(),
             "ICE key must be at least 8 bytes per level"
         );
 
         let mut ice = Ice {
             key: IceKeyStruct {
-                size: if level < 1 { 1 } else { level },
-                rounds: if level < 1 { 8 } else { level * 16 },
-                keysched: Vec::new(),
+                size: level.key_words(),
+                rounds: level.rounds(),
+                keysched: [IceSubkey::default(); MAX_ROUNDS],
             },
-            sbox: ICE_SBOXES,
+            config: None,
         };
 
-        ice.key.keysched = vec![IceSubkey { val: [0; 3] }; ice.key.rounds];
         ice.key_set(key);
         ice
     }
 
-    fn ice_f_batch<const B: usize>(&self, p: [u32; B], sk: &IceSubkey) -> [u32; B] {
-        let mut res = [0u32; B];
-        let s = &self.sbox;
+    /// Create a new ICE instance, reporting an invalid level or short key as an error.
+    ///
+    /// # Errors
+    /// Returns [`IceError::UnsupportedLevel`] if `level` is above 8, or
+    /// [`IceError::KeyTooShort`] if `key` is shorter than 8 bytes per level.
+    pub fn try_new(level: usize, key: &[u8]) -> Result<Self, IceError> {
+        let level = IceLevel::try_from(level)?;
+        if key.len() < level.key_size() {
+            return Err(IceError::KeyTooShort {
+                expected: level.key_size(),
+                actual: key.len(),
+            });
+        }
+        Ok(Self::with_level(level, key))
+    }
 
-        for i in 0..B {
-            let val = p[i];
-            let tr = (val & 0x3ff) | ((val << 2) & 0xffc00);
-            let tl = ((val >> 16) & 0x3ff) | (val.rotate_left(18) & 0xffc00);
+    /// Start building an instance with its own dispatch tuning.
+    ///
+    /// Instances from the other constructors share [`IceConfig::default`].
+    pub fn builder<'a>() -> IceBuilder<'a> {
+        IceBuilder::new()
+    }
 
-            let al_base = sk.val[2] & (tl ^ tr);
-            let al = al_base ^ tl ^ sk.val[0];
-            let ar = al_base ^ tr ^ sk.val[1];
+    /// The dispatch tuning of this instance.
+    pub fn config(&self) -> &IceConfig {
+        self.config.as_ref().unwrap_or_else(|| IceConfig::global())
+    }
+
+    /// The ICE level of this instance.
+    pub fn level(&self) -> IceLevel {
+        IceLevel(if self.key.rounds == 8 {
+            0
+        } else {
+            self.key.size
+        })
+    }
+
+    /// Key size in bytes.
+    pub fn key_size(&self) -> usize {
+        self.level().key_size()
+    }
+
+    /// Block size in bytes.
+    pub fn block_size(&self) -> usize {
+        BLOCK_SIZE
+    }
 
-            res[i] = s[((al >> 10) & 0x3ff) as usize]
-                | s[1024 + (al & 0x3ff) as usize]
-                | s[2048 + ((ar >> 10) & 0x3ff) as usize]
-                | s[3072 + (ar & 0x3ff) as usize];
+    /// Replace the key of this instance, keeping its level.
+    ///
+    /// The previous subkeys are overwritten before the new schedule is built in place.
+    ///
+    /// # Errors
+    /// Returns [`IceError::KeyTooShort`] if `key` is shorter than `self.key_size()`; the
+    /// current key is left unchanged.
+    pub fn set_key(&mut self, key: &[u8]) -> Result<(), IceError> {
+        let expected = self.key_size();
+        if key.len() < expected {
+            return Err(IceError::KeyTooShort {
+                expected,
+                actual: key.len(),
+            });
         }
-        res
+        wipe_subkeys(&mut self.key.keysched);
+        self.key_set(key);
+        Ok(())
     }
 
-    fn process_chunk<const B: usize, const DECRYPT: bool>(&self, chunk: &mut [u8]) {
-        // This assertion allows the compiler to eliminate all bounds checks from the hot loop.
-        assert!(chunk.len() == B * BLOCK_SIZE);
+    /// Overwrite the subkeys, leaving the instance unusable until it is rekeyed.
+    #[cfg(feature = "capi")]
+    pub(crate) fn wipe(&mut self) {
+        wipe_subkeys(&mut self.key.keysched);
+    }
+
+    /// Set the key to be used by the ICE instance.
+    fn key_set(&mut self, key: &[u8]) {
+        let rounds = self.key.rounds;
+        key_set(&mut self.key.keysched[..rounds], self.key.size, key);
+    }
+}
+
+macro_rules! impl_fixed_level {
+    ($ty:ident, $name:literal, $levels:literal, $key_size:literal) => {
+        impl $ty {
+            #[doc = concat!("Create a new ", $name, " instance.")]
+            pub fn new(key: &[u8; $key_size]) -> Self {
+                Self::with_levels($levels, key)
+            }
+
+            /// Replace the key, overwriting the previous subkeys.
+            pub fn set_key(&mut self, key: &[u8; $key_size]) {
+                self.rekey($levels, key);
+            }
+        }
+    };
+}
 
-        let mut l = [0u32; B];
-        let mut r = [0u32; B];
+impl_fixed_level!(IceThin, "Thin-ICE (level 0)", 1, 8);
+impl_fixed_level!(Ice1, "ICE level 1", 1, 8);
+impl_fixed_level!(Ice2, "ICE level 2", 2, 16);
+impl_fixed_level!(Ice3, "ICE level 3", 3, 24);
+impl_fixed_level!(Ice4, "ICE level 4", 4, 32);
+impl_fixed_level!(Ice5, "ICE level 5", 5, 40);
+impl_fixed_level!(Ice6, "ICE level 6", 6, 48);
+impl_fixed_level!(Ice7, "ICE level 7", 7, 56);
+impl_fixed_level!(Ice8, "ICE level 8", 8, 64);
+
+impl<const ROUNDS: usize> IceFixed<ROUNDS> {
+    /// 8-byte key words for this round count: one for Thin-ICE, otherwise one per 16 rounds.
+    pub(crate) const KEY_WORDS: usize = {
+        assert!(
+            ROUNDS == 8 || (ROUNDS.is_multiple_of(16) && ROUNDS >= 16 && ROUNDS <= MAX_ROUNDS),
+            "IceFixed needs the round count of an ICE level"
+        );
+        if ROUNDS == 8 { 1 } else { ROUNDS / 16 }
+    };
+
+    fn with_levels(levels: usize, key: &[u8]) -> Self {
+        let mut keysched = [IceSubkey::default(); ROUNDS];
+        key_set(&mut keysched, levels, key);
+        Self {
+            keysched,
+            config: None,
+        }
+    }
 
-        for i in 0..B {
-            let off = i * 8;
-            l[i] = u32::from_be_bytes(chunk[off..off + 4].try_into().unwrap());
-            r[i] = u32::from_be_bytes(chunk[off + 4..off + 8].try_into().unwrap());
+    /// The instance for `key` with its own `config`, for [`IceBuilder::build_fixed`].
+    pub(crate) fn with_config(key: &[u8], config: IceConfig) -> Result<Self, IceError> {
+        let expected = Self::KEY_WORDS * 8;
+        if key.len() < expected {
+            return Err(IceError::KeyTooShort {
+                expected,
+                actual: key.len(),
+            });
         }
+        let mut ice = Self::with_levels(Self::KEY_WORDS, key);
+        ice.config = Some(config);
+        Ok(ice)
+    }
+
+    /// The dispatch tuning of this instance.
+    pub fn config(&self) -> &IceConfig {
+        self.config.as_ref().unwrap_or_else(|| IceConfig::global())
+    }
+
+    fn rekey(&mut self, levels: usize, key: &[u8]) {
+        wipe_subkeys(&mut self.keysched);
+        key_set(&mut self.keysched, levels, key);
+    }
+
+    /// The round subkeys of the key schedule, in encryption order.
+    pub fn subkeys(&self) -> &[IceSubkey] {
+        &self.keysched
+    }
+}
+
+impl IceKernel for Ice {
+    #[inline(always)]
+    fn rounds<const B: usize, const DECRYPT: bool>(&self, l: &mut [u32; B], r: &mut [u32; B]) {
+        // Hand the kernel a fixed-length schedule so each level gets its own unrolled copy.
+        let ks = &self.key.keysched;
+        match self.key.rounds {
+            8 => feistel::<B, DECRYPT>(ks.first_chunk::<8>().unwrap(), l, r),
+            16 => feistel::<B, DECRYPT>(ks.first_chunk::<16>().unwrap(), l, r),
+            32 => feistel::<B, DECRYPT>(ks.first_chunk::<32>().unwrap(), l, r),
+            _ => feistel::<B, DECRYPT>(self.key.subkeys(), l, r),
+        }
+    }
+
+    fn config(&self) -> &IceConfig {
+        Ice::config(self)
+    }
+
+    fn schedule(&self) -> &[IceSubkey] {
+        self.key.subkeys()
+    }
+}
+
+impl<const ROUNDS: usize> IceKernel for IceFixed<ROUNDS> {
+    #[inline(always)]
+    fn rounds<const B: usize, const DECRYPT: bool>(&self, l: &mut [u32; B], r: &mut [u32; B]) {
+        feistel::<B, DECRYPT>(&self.keysched, l, r);
+    }
+
+    fn config(&self) -> &IceConfig {
+        IceFixed::config(self)
+    }
+
+    fn schedule(&self) -> &[IceSubkey] {
+        &self.keysched
+    }
+}
+
+macro_rules! impl_slice_api {
+    ($ty:ty $(, const $param:ident: $param_ty:ty)?) => {
+        impl$(<const $param: $param_ty>)? $ty {
+            /// Encrypts a single 8-byte block.
+            pub fn encrypt_block(&self, block: [u8; 8]) -> [u8; 8] {
+                self.encrypt_u64(u64::from_be_bytes(block)).to_be_bytes()
+            }
+
+            /// Decrypts a single 8-byte block.
+            pub fn decrypt_block(&self, block: [u8; 8]) -> [u8; 8] {
+                self.decrypt_u64(u64::from_be_bytes(block)).to_be_bytes()
+            }
+
+            /// Encrypts a block given as a big-endian `u64`.
+            pub fn encrypt_u64(&self, block: u64) -> u64 {
+                let (l, r) = self.encrypt_lr((block >> 32) as u32, block as u32);
+                ((l as u64) << 32) | r as u64
+            }
+
+            /// Decrypts a block given as a big-endian `u64`.
+            pub fn decrypt_u64(&self, block: u64) -> u64 {
+                let (l, r) = self.decrypt_lr((block >> 32) as u32, block as u32);
+                ((l as u64) << 32) | r as u64
+            }
+
+            /// Encrypts a block given as its big-endian left and right halves, returning the
+            /// ciphertext halves in the same order.
+            pub fn encrypt_lr(&self, l: u32, r: u32) -> (u32, u32) {
+                self.process_lr::<false>(l, r)
+            }
+
+            /// Decrypts a block given as its big-endian left and right halves, returning the
+            /// plaintext halves in the same order.
+            pub fn decrypt_lr(&self, l: u32, r: u32) -> (u32, u32) {
+                self.process_lr::<true>(l, r)
+            }
+
+            /// Encrypts the provided data in-place.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of 8.
+            pub fn encrypt(&self, data: &mut [u8]) {
+                self.dispatch_serial::<false>(data);
+            }
+
+            /// Decrypts the provided data in-place.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of 8.
+            pub fn decrypt(&self, data: &mut [u8]) {
+                self.dispatch_serial::<true>(data);
+            }
+
+            /// Encrypts the provided data in-place in parallel.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a multiple of 8.
+            #[cfg(feature = "parallel")]
+            pub fn encrypt_par(&self, data: &mut [u8]) {
+                self.config().run_par(|| self.dispatch_par::<false>(data));
+            }
+
+            /// Decrypts the provided data in-place in parallel.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a multiple of 8.
+            #[cfg(feature = "parallel")]
+            pub fn decrypt_par(&self, data: &mut [u8]) {
+                self.config().run_par(|| self.dispatch_par::<true>(data));
+            }
+
+            /// Encrypts the provided data in-place in parallel on `pool` rather than the global
+            /// rayon pool.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a multiple of 8.
+            #[cfg(feature = "parallel")]
+            pub fn encrypt_par_in(&self, pool: &ThreadPool, data: &mut [u8]) {
+                pool.install(|| self.dispatch_par::<false>(data));
+            }
+
+            /// Decrypts the provided data in-place in parallel on `pool` rather than the global
+            /// rayon pool.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a multiple of 8.
+            #[cfg(feature = "parallel")]
+            pub fn decrypt_par_in(&self, pool: &ThreadPool, data: &mut [u8]) {
+                pool.install(|| self.dispatch_par::<true>(data));
+            }
+
+            /// Encrypts the provided data in-place.
+            ///
+            /// Switches between serial and parallel processing based on input length (see [`IceConfig`]).
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of 8.
+            #[allow(unused)]
+            pub fn encrypt_auto(&self, data: &mut [u8]) {
+                self.dispatch_auto::<false>(data);
+            }
+
+            /// Decrypts the provided data in-place.
+            ///
+            /// Switches between serial and parallel processing based on input length (see [`IceConfig`]).
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of 8.
+            #[allow(unused)]
+            pub fn decrypt_auto(&self, data: &mut [u8]) {
+                self.dispatch_auto::<true>(data);
+            }
+
+            /// Encrypts the provided data in-place, running any parallel work on `pool`.
+            ///
+            /// Switches between serial and parallel processing based on input length (see [`IceConfig`]) and
+            /// the thread count of `pool`.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of 8.
+            #[cfg(feature = "parallel")]
+            pub fn encrypt_auto_in(&self, pool: &ThreadPool, data: &mut [u8]) {
+                self.dispatch_auto_in::<false>(pool, data);
+            }
+
+            /// Decrypts the provided data in-place, running any parallel work on `pool`.
+            ///
+            /// Switches between serial and parallel processing based on input length (see [`IceConfig`]) and
+            /// the thread count of `pool`.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of 8.
+            #[cfg(feature = "parallel")]
+            pub fn decrypt_auto_in(&self, pool: &ThreadPool, data: &mut [u8]) {
+                self.dispatch_auto_in::<true>(pool, data);
+            }
+
+            /// Encrypts `src` into `dst`, leaving `src` untouched.
+            ///
+            /// # Panics
+            /// Panics if `src.len()` is not a positive multiple of 8 or `dst.len() != src.len()`.
+            pub fn encrypt_to(&self, src: &[u8], dst: &mut [u8]) {
+                self.dispatch_serial_to::<false>(src, dst);
+            }
+
+            /// Decrypts `src` into `dst`, leaving `src` untouched.
+            ///
+            /// # Panics
+            /// Panics if `src.len()` is not a positive multiple of 8 or `dst.len() != src.len()`.
+            pub fn decrypt_to(&self, src: &[u8], dst: &mut [u8]) {
+                self.dispatch_serial_to::<true>(src, dst);
+            }
+
+            /// Encrypts `src` into `dst` in parallel, leaving `src` untouched.
+            ///
+            /// # Panics
+            /// Panics if `src.len()` is not a positive multiple of 8 or `dst.len() != src.len()`.
+            #[cfg(feature = "parallel")]
+            pub fn encrypt_par_to(&self, src: &[u8], dst: &mut [u8]) {
+                self.config().run_par(|| self.dispatch_par_to::<false>(src, dst));
+            }
+
+            /// Decrypts `src` into `dst` in parallel, leaving `src` untouched.
+            ///
+            /// # Panics
+            /// Panics if `src.len()` is not a positive multiple of 8 or `dst.len() != src.len()`.
+            #[cfg(feature = "parallel")]
+            pub fn decrypt_par_to(&self, src: &[u8], dst: &mut [u8]) {
+                self.config().run_par(|| self.dispatch_par_to::<true>(src, dst));
+            }
+
+            /// Encrypts `src` into `dst`, leaving `src` untouched.
+            ///
+            /// Switches between serial and parallel processing based on input length (see [`IceConfig`]).
+            ///
+            /// # Panics
+            /// Panics if `src.len()` is not a positive multiple of 8 or `dst.len() != src.len()`.
+            pub fn encrypt_auto_to(&self, src: &[u8], dst: &mut [u8]) {
+                self.dispatch_auto_to::<false>(src, dst);
+            }
 
-        if DECRYPT {
-            for pair in self.key.keysched.rchunks_exact(2) {
-                let f_r = self.ice_f_batch::<B>(r, &pair[1]);
-                for i in 0..B {
-                    l[i] ^= f_r[i];
+            /// Decrypts `src` into `dst`, leaving `src` untouched.
+            ///
+            /// Switches between serial and parallel processing based on input length (see [`IceConfig`]).
+            ///
+            /// # Panics
+            /// Panics if `src.len()` is not a positive multiple of 8 or `dst.len() != src.len()`.
+            pub fn decrypt_auto_to(&self, src: &[u8], dst: &mut [u8]) {
+                self.dispatch_auto_to::<true>(src, dst);
+            }
+
+            /// Encrypts the provided data in-place.
+            ///
+            /// Empty input is a no-op.
+            ///
+            /// # Errors
+            /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+            pub fn try_encrypt(&self, data: &mut [u8]) -> Result<(), IceError> {
+                if check_block_aligned(data)? {
+                    self.dispatch_serial::<false>(data);
                 }
-                let f_l = self.ice_f_batch::<B>(l, &pair[0]);
-                for i in 0..B {
-                    r[i] ^= f_l[i];
+                Ok(())
+            }
+
+            /// Decrypts the provided data in-place.
+            ///
+            /// Empty input is a no-op.
+            ///
+            /// # Errors
+            /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+            pub fn try_decrypt(&self, data: &mut [u8]) -> Result<(), IceError> {
+                if check_block_aligned(data)? {
+                    self.dispatch_serial::<true>(data);
                 }
+                Ok(())
             }
-        } else {
-            for pair in self.key.keysched.chunks_exact(2) {
-                let f_r = self.ice_f_batch::<B>(r, &pair[0]);
-                for i in 0..B {
-                    l[i] ^= f_r[i];
+
+            /// Encrypts the provided data in-place in parallel.
+            ///
+            /// Empty input is a no-op.
+            ///
+            /// # Errors
+            /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+            #[cfg(feature = "parallel")]
+            pub fn try_encrypt_par(&self, data: &mut [u8]) -> Result<(), IceError> {
+                if check_block_aligned(data)? {
+                    self.config().run_par(|| self.dispatch_par::<false>(data));
+                }
+                Ok(())
+            }
+
+            /// Decrypts the provided data in-place in parallel.
+            ///
+            /// Empty input is a no-op.
+            ///
+            /// # Errors
+            /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+            #[cfg(feature = "parallel")]
+            pub fn try_decrypt_par(&self, data: &mut [u8]) -> Result<(), IceError> {
+                if check_block_aligned(data)? {
+                    self.config().run_par(|| self.dispatch_par::<true>(data));
+                }
+                Ok(())
+            }
+
+            /// Encrypts the provided data in-place, switching between serial and parallel
+            /// processing based on input length.
+            ///
+            /// Empty input is a no-op.
+            ///
+            /// # Errors
+            /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+            pub fn try_encrypt_auto(&self, data: &mut [u8]) -> Result<(), IceError> {
+                if check_block_aligned(data)? {
+                    self.dispatch_auto::<false>(data);
                 }
-                let f_l = self.ice_f_batch::<B>(l, &pair[1]);
-                for i in 0..B {
-                    r[i] ^= f_l[i];
+                Ok(())
+            }
+
+            /// Decrypts the provided data in-place, switching between serial and parallel
+            /// processing based on input length.
+            ///
+            /// Empty input is a no-op.
+            ///
+            /// # Errors
+            /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+            pub fn try_decrypt_auto(&self, data: &mut [u8]) -> Result<(), IceError> {
+                if check_block_aligned(data)? {
+                    self.dispatch_auto::<true>(data);
                 }
+                Ok(())
+            }
+
+            /// Encrypts the provided data in-place, handling any trailing partial block
+            /// according to `residual`.
+            ///
+            /// Switches between serial and parallel processing based on input length (see [`IceConfig`]).
+            /// Empty input is a no-op unless `residual` is [`Residual::Panic`].
+            ///
+            /// # Errors
+            /// Returns [`IceError::NotBlockAligned`] for a partial block with [`Residual::Error`].
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of 8 with [`Residual::Panic`].
+            pub fn encrypt_auto_with(&self, data: &mut [u8], residual: Residual) -> Result<(), IceError> {
+                self.dispatch_residual::<false>(data, residual)
+            }
+
+            /// Decrypts the provided data in-place, handling any trailing partial block
+            /// according to `residual`.
+            ///
+            /// Switches between serial and parallel processing based on input length (see [`IceConfig`]).
+            /// Empty input is a no-op unless `residual` is [`Residual::Panic`].
+            ///
+            /// # Errors
+            /// Returns [`IceError::NotBlockAligned`] for a partial block with [`Residual::Error`].
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of 8 with [`Residual::Panic`].
+            pub fn decrypt_auto_with(&self, data: &mut [u8], residual: Residual) -> Result<(), IceError> {
+                self.dispatch_residual::<true>(data, residual)
+            }
+
+            /// Encrypts the provided data in-place using B 8-byte blocks.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of B.
+            #[allow(unused)]
+            pub fn encrypt_chunks<const B: usize>(&self, data: &mut [u8]) {
+                assert!(data.len().is_multiple_of(BLOCK_SIZE) && data.len() >= B * BLOCK_SIZE);
+                let kernel = self.kernel::<B>();
+                data.chunks_exact_mut(B * BLOCK_SIZE)
+                    .for_each(|c| self.process_chunk::<B, false>(kernel, c));
+            }
+
+            /// Encrypts the provided data in-place using B 8-byte blocks in parallel.
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of B.
+            #[allow(unused)]
+            #[cfg(feature = "parallel")]
+            pub fn encrypt_chunks_par<const B: usize>(&self, data: &mut [u8]) {
+                assert!(data.len().is_multiple_of(BLOCK_SIZE) && data.len() >= B * BLOCK_SIZE);
+                let kernel = self.kernel::<B>();
+                self.config().run_par(|| {
+                    data.par_chunks_exact_mut(B * BLOCK_SIZE)
+                        .for_each(|c| self.process_chunk::<B, false>(kernel, c))
+                });
+            }
+
+            /// Decrypts the provided data in-place using B 8-byte blocks
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of B.
+            #[allow(unused)]
+            pub fn decrypt_chunks<const B: usize>(&self, data: &mut [u8]) {
+                assert!(data.len().is_multiple_of(BLOCK_SIZE) && data.len() >= B * BLOCK_SIZE);
+                let kernel = self.kernel::<B>();
+                data.chunks_exact_mut(B * BLOCK_SIZE)
+                    .for_each(|c| self.process_chunk::<B, true>(kernel, c));
+            }
+
+            /// Decrypts the provided data in-place using B 8-byte blocks in parallel
+            ///
+            /// # Panics
+            /// Panics if `data.len()` is not a positive multiple of B.
+            #[allow(unused)]
+            #[cfg(feature = "parallel")]
+            pub fn decrypt_chunks_par<const B: usize>(&self, data: &mut [u8]) {
+                assert!(data.len().is_multiple_of(BLOCK_SIZE) && data.len() >= B * BLOCK_SIZE);
+                let kernel = self.kernel::<B>();
+                self.config().run_par(|| {
+                    data.par_chunks_exact_mut(B * BLOCK_SIZE)
+                        .for_each(|c| self.process_chunk::<B, true>(kernel, c))
+                });
             }
         }
+    };
+}
+
+impl_slice_api!(Ice);
+impl_slice_api!(IceFixed<ROUNDS>, const ROUNDS: usize);
+
+/// Batched block processing and dispatch shared by `Ice` and `IceFixed`.
+///
+/// Implementors only provide the Feistel rounds; loading, storing and dispatch are common.
+pub(crate) trait IceKernel: Sync {
+    fn rounds<const B: usize, const DECRYPT: bool>(&self, l: &mut [u32; B], r: &mut [u32; B]);
+
+    fn config(&self) -> &IceConfig;
 
-        for i in 0..B {
-            let off = i * 8;
-            chunk[off..off + 4].copy_from_slice(&r[i].to_be_bytes());
-            chunk[off + 4..off + 8].copy_from_slice(&l[i].to_be_bytes());
+    /// The active round subkeys, in encryption order.
+    fn schedule(&self) -> &[IceSubkey];
+
+    /// The configured backend's kernel for batches of `B` blocks, if it has one.
+    ///
+    /// Callers look it up once per dispatch and pass it to every chunk.
+    #[inline(always)]
+    fn kernel<const B: usize>(&self) -> Option<Kernel> {
+        self.config().backend().kernel(B)
+    }
+
+    /// Runs the rounds with `kernel`, or the generic rounds without one.
+    #[inline(always)]
+    fn backend_rounds<const B: usize, const DECRYPT: bool>(
+        &self,
+        kernel: Option<Kernel>,
+        l: &mut [u32; B],
+        r: &mut [u32; B],
+    ) {
+        match kernel {
+            // SAFETY: configurations only hold backends that are available on this CPU.
+            Some(kernel) => unsafe { kernel(self.schedule(), DECRYPT, l, r) },
+            None => self.rounds::<B, DECRYPT>(l, r),
         }
     }
 
-    /// Encrypts the provided data in-place.
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a positive multiple of 8.
-    pub fn encrypt(&self, data: &mut [u8]) {
-        self.dispatch_serial::<false>(data);
+    #[inline(always)]
+    fn process_lr<const DECRYPT: bool>(&self, l: u32, r: u32) -> (u32, u32) {
+        let (mut l, mut r) = ([l], [r]);
+        self.backend_rounds::<1, DECRYPT>(self.kernel::<1>(), &mut l, &mut r);
+        // The halves swap on output, as in `store_blocks`.
+        (r[0], l[0])
     }
 
-    /// Decrypts the provided data in-place.
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a positive multiple of 8.
-    pub fn decrypt(&self, data: &mut [u8]) {
-        self.dispatch_serial::<true>(data);
+    fn process_chunk<const B: usize, const DECRYPT: bool>(
+        &self,
+        kernel: Option<Kernel>,
+        chunk: &mut [u8],
+    ) {
+        // This assertion allows the compiler to eliminate all bounds checks from the hot loop.
+        assert!(chunk.len() == B * BLOCK_SIZE);
+
+        let (mut l, mut r) = load_blocks::<B>(chunk);
+        self.backend_rounds::<B, DECRYPT>(kernel, &mut l, &mut r);
+        store_blocks::<B>(&l, &r, chunk);
+    }
+
+    fn process_chunk_to<const B: usize, const DECRYPT: bool>(
+        &self,
+        kernel: Option<Kernel>,
+        src: &[u8],
+        dst: &mut [u8],
+    ) {
+        assert!(src.len() == B * BLOCK_SIZE && dst.len() == B * BLOCK_SIZE);
+
+        let (mut l, mut r) = load_blocks::<B>(src);
+        self.backend_rounds::<B, DECRYPT>(kernel, &mut l, &mut r);
+        store_blocks::<B>(&l, &r, dst);
     }
 
     fn dispatch_serial<const DECRYPT: bool>(&self, data: &mut [u8]) {
@@ -222,8 +916,7 @@ impl Ice {
         assert!(len.is_multiple_of(BLOCK_SIZE) && len >= BLOCK_SIZE);
 
         let blocks = len / BLOCK_SIZE;
-        let prev_pow_2 = std::cmp::min(1usize << blocks.ilog2(), MAX_BLOCKS_PER_BATCH_SERIAL);
-        let pow_2_exponent = prev_pow_2.ilog2();
+        let pow_2_exponent = blocks.ilog2().min(self.config().max_serial_batch_exp());
 
         match pow_2_exponent {
             0 => self.process_serial::<1, DECRYPT>(data),
@@ -248,38 +941,23 @@ impl Ice {
         let chunk_size: usize = B * BLOCK_SIZE;
         let (head, tail) = { data.split_at_mut((len / chunk_size) * chunk_size) };
 
+        let kernel = self.kernel::<B>();
         head.chunks_exact_mut(chunk_size)
-            .for_each(|c| self.process_chunk::<B, DECRYPT>(c));
+            .for_each(|c| self.process_chunk::<B, DECRYPT>(kernel, c));
 
         if tail.len() >= BLOCK_SIZE {
             self.dispatch_serial::<DECRYPT>(tail);
         }
     }
 
-    /// Encrypts the provided data in-place in parallel.
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a multiple of 8.
-    pub fn encrypt_par(&self, data: &mut [u8]) {
-        self.dispatch_par::<false>(data);
-    }
-
-    /// Decrypts the provided data in-place in parallel.
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a multiple of 8.
-    pub fn decrypt_par(&self, data: &mut [u8]) {
-        self.dispatch_par::<true>(data);
-    }
-
+    #[cfg(feature = "parallel")]
     fn dispatch_par<const DECRYPT: bool>(&self, data: &mut [u8]) {
         let len = data.len();
 
         assert!(len.is_multiple_of(BLOCK_SIZE) && len >= BLOCK_SIZE);
 
         let blocks = len / BLOCK_SIZE;
-        let prev_pow_2 = std::cmp::min(1usize << blocks.ilog2(), MAX_BLOCKS_PER_BATCH_PARALLEL);
-        let pow_2_exponent = prev_pow_2.ilog2();
+        let pow_2_exponent = blocks.ilog2().min(self.config().max_parallel_batch_exp());
 
         match pow_2_exponent {
             0 => self.process_par::<1, DECRYPT>(data),
@@ -295,6 +973,7 @@ impl Ice {
         }
     }
 
+    #[cfg(feature = "parallel")]
     fn process_par<const B: usize, const DECRYPT: bool>(&self, data: &mut [u8]) {
         let len = data.len();
 
@@ -303,133 +982,315 @@ impl Ice {
         let chunk_size: usize = B * BLOCK_SIZE;
         let (head, tail) = { data.split_at_mut((len / chunk_size) * chunk_size) };
 
+        let kernel = self.kernel::<B>();
         head.par_chunks_exact_mut(chunk_size)
-            .for_each(|c| self.process_chunk::<B, DECRYPT>(c));
+            .for_each(|c| self.process_chunk::<B, DECRYPT>(kernel, c));
 
         if tail.len() >= BLOCK_SIZE {
             self.dispatch_par::<DECRYPT>(tail);
         }
     }
 
-    /// Encrypts the provided data in-place.
-    ///
-    /// Switches between serial and parallel processing based on input length (32 KB).
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a positive multiple of 8.
-    #[allow(unused)]
-    pub fn encrypt_auto(&self, data: &mut [u8]) {
-        if data.len() >= AUTO_PAR_THRESHOLD {
-            if rayon::current_num_threads() < 2 {
-                self.dispatch_serial::<false>(data);
-                return;
-            }
-            self.dispatch_par::<false>(data);
-        } else {
-            self.dispatch_serial::<false>(data);
+    fn dispatch_auto<const DECRYPT: bool>(&self, data: &mut [u8]) {
+        #[cfg(feature = "parallel")]
+        if self.config().auto_parallel(data.len()) {
+            return self.config().run_par(|| self.dispatch_par::<DECRYPT>(data));
         }
+        self.dispatch_serial::<DECRYPT>(data);
     }
 
-    /// Decrypts the provided data in-place.
-    ///
-    /// Switches between serial and parallel processing based on input length (32 KB).
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a positive multiple of 8.
-    #[allow(unused)]
-    pub fn decrypt_auto(&self, data: &mut [u8]) {
+    #[cfg(feature = "parallel")]
+    fn dispatch_auto_in<const DECRYPT: bool>(&self, pool: &ThreadPool, data: &mut [u8]) {
+        if data.len() >= self.config().parallel_threshold() && pool.current_num_threads() >= 2 {
+            return pool.install(|| self.dispatch_par::<DECRYPT>(data));
+        }
+        self.dispatch_serial::<DECRYPT>(data);
+    }
+
+    fn dispatch_residual<const DECRYPT: bool>(
+        &self,
+        data: &mut [u8],
+        residual: Residual,
+    ) -> Result<(), IceError> {
         let len = data.len();
-        if len >= AUTO_PAR_THRESHOLD {
-            if rayon::current_num_threads() < 2 {
-                self.dispatch_serial::<true>(data);
-                return;
+        let full = len / BLOCK_SIZE * BLOCK_SIZE;
+        match residual {
+            Residual::Panic => {
+                self.dispatch_auto::<DECRYPT>(data);
+                return Ok(());
             }
-            self.dispatch_par::<true>(data);
-        } else {
-            self.dispatch_serial::<true>(data);
+            Residual::Error if full != len => return Err(IceError::NotBlockAligned(len)),
+            _ => {}
+        }
+
+        let (blocks, tail) = data.split_at_mut(full);
+        // The keystream input is the last whole ciphertext block, before decryption or after
+        // encryption.
+        let mut feedback = [0u8; 8];
+        if DECRYPT && !blocks.is_empty() {
+            feedback.copy_from_slice(&blocks[full - BLOCK_SIZE..]);
+        }
+        if !blocks.is_empty() {
+            self.dispatch_auto::<DECRYPT>(blocks);
         }
+        if !DECRYPT && !blocks.is_empty() {
+            feedback.copy_from_slice(&blocks[full - BLOCK_SIZE..]);
+        }
+
+        if residual == Residual::XorWithKeystream && !tail.is_empty() {
+            let keystream = self.process_lr::<false>(
+                u32::from_be_bytes(feedback[..4].try_into().unwrap()),
+                u32::from_be_bytes(feedback[4..].try_into().unwrap()),
+            );
+            let keystream = (((keystream.0 as u64) << 32) | keystream.1 as u64).to_be_bytes();
+            for (byte, k) in tail.iter_mut().zip(keystream) {
+                *byte ^= k;
+            }
+        }
+        Ok(())
     }
 
-    /// Encrypts the provided data in-place using B 8-byte blocks.
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a positive multiple of B.
-    #[allow(unused)]
-    pub fn encrypt_chunks<const B: usize>(&self, data: &mut [u8]) {
-        assert!(data.len().is_multiple_of(BLOCK_SIZE) && data.len() >= B * BLOCK_SIZE);
-        data.chunks_exact_mut(B * BLOCK_SIZE)
-            .for_each(|c| self.process_chunk::<B, false>(c));
+    fn dispatch_serial_to<const DECRYPT: bool>(&self, src: &[u8], dst: &mut [u8]) {
+        let len = src.len();
+
+        assert!(len.is_multiple_of(BLOCK_SIZE) && len >= BLOCK_SIZE);
+        assert!(
+            dst.len() == len,
+            "source and destination lengths must match"
+        );
+
+        let blocks = len / BLOCK_SIZE;
+        let pow_2_exponent = blocks.ilog2().min(self.config().max_serial_batch_exp());
+
+        match pow_2_exponent {
+            0 => self.process_serial_to::<1, DECRYPT>(src, dst),
+            1 => self.process_serial_to::<2, DECRYPT>(src, dst),
+            2 => self.process_serial_to::<4, DECRYPT>(src, dst),
+            3 => self.process_serial_to::<8, DECRYPT>(src, dst),
+            4 => self.process_serial_to::<16, DECRYPT>(src, dst),
+            5 => self.process_serial_to::<32, DECRYPT>(src, dst),
+            6 => self.process_serial_to::<64, DECRYPT>(src, dst),
+            7 => self.process_serial_to::<128, DECRYPT>(src, dst),
+            8 => self.process_serial_to::<256, DECRYPT>(src, dst),
+            9 => self.process_serial_to::<512, DECRYPT>(src, dst),
+            _ => unreachable!("pow_2_exponent should be guaranteed to be between 0 and 9"),
+        }
     }
 
-    /// Encrypts the provided data in-place using B 8-byte blocks in parallel.
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a positive multiple of B.
-    #[allow(unused)]
-    pub fn encrypt_chunks_par<const B: usize>(&self, data: &mut [u8]) {
-        assert!(data.len().is_multiple_of(BLOCK_SIZE) && data.len() >= B * BLOCK_SIZE);
-        data.par_chunks_exact_mut(B * BLOCK_SIZE)
-            .for_each(|c| self.process_chunk::<B, false>(c));
+    fn process_serial_to<const B: usize, const DECRYPT: bool>(&self, src: &[u8], dst: &mut [u8]) {
+        let len = src.len();
+
+        assert!(len.is_multiple_of(BLOCK_SIZE));
+
+        let chunk_size: usize = B * BLOCK_SIZE;
+        let split = (len / chunk_size) * chunk_size;
+        let (src_head, src_tail) = src.split_at(split);
+        let (dst_head, dst_tail) = dst.split_at_mut(split);
+
+        let kernel = self.kernel::<B>();
+        src_head
+            .chunks_exact(chunk_size)
+            .zip(dst_head.chunks_exact_mut(chunk_size))
+            .for_each(|(s, d)| self.process_chunk_to::<B, DECRYPT>(kernel, s, d));
+
+        if src_tail.len() >= BLOCK_SIZE {
+            self.dispatch_serial_to::<DECRYPT>(src_tail, dst_tail);
+        }
     }
 
-    /// Decrypts the provided data in-place using B 8-byte blocks
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a positive multiple of B.
-    #[allow(unused)]
-    pub fn decrypt_chunks<const B: usize>(&self, data: &mut [u8]) {
-        assert!(data.len().is_multiple_of(BLOCK_SIZE) && data.len() >= B * BLOCK_SIZE);
-        data.chunks_exact_mut(B * BLOCK_SIZE)
-            .for_each(|c| self.process_chunk::<B, true>(c));
+    #[cfg(feature = "parallel")]
+    fn dispatch_par_to<const DECRYPT: bool>(&self, src: &[u8], dst: &mut [u8]) {
+        let len = src.len();
+
+        assert!(len.is_multiple_of(BLOCK_SIZE) && len >= BLOCK_SIZE);
+        assert!(
+            dst.len() == len,
+            "source and destination lengths must match"
+        );
+
+        let blocks = len / BLOCK_SIZE;
+        let pow_2_exponent = blocks.ilog2().min(self.config().max_parallel_batch_exp());
+
+        match pow_2_exponent {
+            0 => self.process_par_to::<1, DECRYPT>(src, dst),
+            1 => self.process_par_to::<2, DECRYPT>(src, dst),
+            2 => self.process_par_to::<4, DECRYPT>(src, dst),
+            3 => self.process_par_to::<8, DECRYPT>(src, dst),
+            4 => self.process_par_to::<16, DECRYPT>(src, dst),
+            5 => self.process_par_to::<32, DECRYPT>(src, dst),
+            6 => self.process_par_to::<64, DECRYPT>(src, dst),
+            7 => self.process_par_to::<128, DECRYPT>(src, dst),
+            8 => self.process_par_to::<256, DECRYPT>(src, dst),
+            _ => unreachable!("pow_2_exponent should be guaranteed to be between 0 and 8"),
+        }
     }
 
-    /// Decrypts the provided data in-place using B 8-byte blocks in parallel
-    ///
-    /// # Panics
-    /// Panics if `data.len()` is not a positive multiple of B.
-    #[allow(unused)]
-    pub fn decrypt_chunks_par<const B: usize>(&self, data: &mut [u8]) {
-        assert!(data.len().is_multiple_of(BLOCK_SIZE) && data.len() >= B * BLOCK_SIZE);
-        data.par_chunks_exact_mut(B * BLOCK_SIZE)
-            .for_each(|c| self.process_chunk::<B, true>(c));
-    }
-
-    fn key_sched_build(&mut self, kb: &mut [u16; 4], n: usize, keyrot: &[i32]) {
-        for (i, &kr) in keyrot.iter().enumerate().take(8) {
-            let isk = &mut self.key.keysched[n + i];
-            isk.val.fill(0);
-            for j in 0..15 {
-                let curr_sk = &mut isk.val[j % 3];
-                for k in 0..4 {
-                    let curr_kb = &mut kb[((kr + k) & 3) as usize];
-                    let bit = *curr_kb & 1;
-                    *curr_sk = (*curr_sk << 1) | bit as u32;
-                    *curr_kb = (*curr_kb >> 1) | ((bit ^ 1) << 15);
-                }
-            }
+    #[cfg(feature = "parallel")]
+    fn process_par_to<const B: usize, const DECRYPT: bool>(&self, src: &[u8], dst: &mut [u8]) {
+        let len = src.len();
+
+        assert!(len.is_multiple_of(BLOCK_SIZE));
+
+        let chunk_size: usize = B * BLOCK_SIZE;
+        let split = (len / chunk_size) * chunk_size;
+        let (src_head, src_tail) = src.split_at(split);
+        let (dst_head, dst_tail) = dst.split_at_mut(split);
+
+        let kernel = self.kernel::<B>();
+        src_head
+            .par_chunks_exact(chunk_size)
+            .zip(dst_head.par_chunks_exact_mut(chunk_size))
+            .for_each(|(s, d)| self.process_chunk_to::<B, DECRYPT>(kernel, s, d));
+
+        if src_tail.len() >= BLOCK_SIZE {
+            self.dispatch_par_to::<DECRYPT>(src_tail, dst_tail);
         }
     }
 
-    /// Set the key to be used by the ICE instance.
-    fn key_set(&mut self, key: &[u8]) {
-        let levels = self.key.size;
-        if levels == 1 && self.key.rounds == 8 {
-            let mut kb = [0u16; 4];
-            for i in 0..4 {
-                kb[3 - i] = u16::from_be_bytes([key[i * 2], key[i * 2 + 1]]);
+    fn dispatch_auto_to<const DECRYPT: bool>(&self, src: &[u8], dst: &mut [u8]) {
+        #[cfg(feature = "parallel")]
+        if self.config().auto_parallel(src.len()) {
+            return self
+                .config()
+                .run_par(|| self.dispatch_par_to::<DECRYPT>(src, dst));
+        }
+        self.dispatch_serial_to::<DECRYPT>(src, dst);
+    }
+}
+
+/// Loads `B` big-endian blocks as left and right Feistel halves.
+#[inline(always)]
+fn load_blocks<const B: usize>(src: &[u8]) -> ([u32; B], [u32; B]) {
+    let mut l = [0u32; B];
+    let mut r = [0u32; B];
+
+    for i in 0..B {
+        let off = i * 8;
+        l[i] = u32::from_be_bytes(src[off..off + 4].try_into().unwrap());
+        r[i] = u32::from_be_bytes(src[off + 4..off + 8].try_into().unwrap());
+    }
+    (l, r)
+}
+
+/// Stores `B` blocks with the halves swapped, completing the final Feistel round.
+#[inline(always)]
+fn store_blocks<const B: usize>(l: &[u32; B], r: &[u32; B], dst: &mut [u8]) {
+    for i in 0..B {
+        let off = i * 8;
+        dst[off..off + 4].copy_from_slice(&r[i].to_be_bytes());
+        dst[off + 4..off + 8].copy_from_slice(&l[i].to_be_bytes());
+    }
+}
+
+/// Returns `Ok(true)` if `data` holds at least one whole block and nothing else,
+/// `Ok(false)` if it is empty.
+pub(crate) fn check_block_aligned(data: &[u8]) -> Result<bool, IceError> {
+    if !data.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(IceError::NotBlockAligned(data.len()));
+    }
+    Ok(!data.is_empty())
+}
+
+fn ice_f_batch<const B: usize>(p: [u32; B], sk: &IceSubkey) -> [u32; B] {
+    let mut res = [0u32; B];
+    let s = &ICE_SBOXES;
+
+    for i in 0..B {
+        let val = p[i];
+        let tr = (val & 0x3ff) | ((val << 2) & 0xffc00);
+        let tl = ((val >> 16) & 0x3ff) | (val.rotate_left(18) & 0xffc00);
+
+        let al_base = sk.val[2] & (tl ^ tr);
+        let al = al_base ^ tl ^ sk.val[0];
+        let ar = al_base ^ tr ^ sk.val[1];
+
+        res[i] = s[((al >> 10) & 0x3ff) as usize]
+            | s[1024 + (al & 0x3ff) as usize]
+            | s[2048 + ((ar >> 10) & 0x3ff) as usize]
+            | s[3072 + (ar & 0x3ff) as usize];
+    }
+    res
+}
+
+/// Runs the Feistel network over `B` blocks. Callers pass a fixed-length schedule where they
+/// can so the round loop is unrolled.
+#[inline(always)]
+pub(crate) fn feistel<const B: usize, const DECRYPT: bool>(
+    keysched: &[IceSubkey],
+    l: &mut [u32; B],
+    r: &mut [u32; B],
+) {
+    if DECRYPT {
+        for pair in keysched.rchunks_exact(2) {
+            let f_r = ice_f_batch::<B>(*r, &pair[1]);
+            for i in 0..B {
+                l[i] ^= f_r[i];
+            }
+            let f_l = ice_f_batch::<B>(*l, &pair[0]);
+            for i in 0..B {
+                r[i] ^= f_l[i];
+            }
+        }
+    } else {
+        for pair in keysched.chunks_exact(2) {
+            let f_r = ice_f_batch::<B>(*r, &pair[0]);
+            for i in 0..B {
+                l[i] ^= f_r[i];
+            }
+            let f_l = ice_f_batch::<B>(*l, &pair[1]);
+            for i in 0..B {
+                r[i] ^= f_l[i];
             }
-            self.key_sched_build(&mut kb, 0, &KEYROT);
-            return;
         }
+    }
+}
 
-        for i in 0..levels {
-            let mut kb = [0u16; 4];
-            for j in 0..4 {
-                let base = i * 8 + j * 2;
-                kb[3 - j] = u16::from_be_bytes([key[base], key[base + 1]]);
+/// Zero the subkeys with volatile writes so the stores are not elided.
+fn wipe_subkeys(keysched: &mut [IceSubkey]) {
+    for sk in keysched.iter_mut() {
+        // SAFETY: `sk` is a valid, aligned, exclusive reference.
+        unsafe { core::ptr::write_volatile(sk, IceSubkey::default()) };
+    }
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+fn key_sched_build(keysched: &mut [IceSubkey], kb: &mut [u16; 4], n: usize, keyrot: &[i32]) {
+    for (i, &kr) in keyrot.iter().enumerate().take(8) {
+        let isk = &mut keysched[n + i];
+        isk.val.fill(0);
+        for j in 0..15 {
+            let curr_sk = &mut isk.val[j % 3];
+            for k in 0..4 {
+                let curr_kb = &mut kb[((kr + k) & 3) as usize];
+                let bit = *curr_kb & 1;
+                *curr_sk = (*curr_sk << 1) | bit as u32;
+                *curr_kb = (*curr_kb >> 1) | ((bit ^ 1) << 15);
             }
-            self.key_sched_build(&mut kb, i * 8, &KEYROT);
-            self.key_sched_build(&mut kb, self.key.rounds - 8 - i * 8, &KEYROT[8..16]);
         }
     }
 }
+
+/// Build the key schedule for `levels` 8-byte key words into `keysched`.
+///
+/// The schedule length is the round count; 8 rounds selects Thin-ICE.
+fn key_set(keysched: &mut [IceSubkey], levels: usize, key: &[u8]) {
+    let rounds = keysched.len();
+    if levels == 1 && rounds == 8 {
+        let mut kb = [0u16; 4];
+        for i in 0..4 {
+            kb[3 - i] = u16::from_be_bytes([key[i * 2], key[i * 2 + 1]]);
+        }
+        key_sched_build(keysched, &mut kb, 0, &KEYROT);
+        return;
+    }
+
+    for i in 0..levels {
+        let mut kb = [0u16; 4];
+        for j in 0..4 {
+            let base = i * 8 + j * 2;
+            kb[3 - j] = u16::from_be_bytes([key[base], key[base + 1]]);
+        }
+        key_sched_build(keysched, &mut kb, i * 8, &KEYROT);
+        key_sched_build(keysched, &mut kb, rounds - 8 - i * 8, &KEYROT[8..16]);
+    }
+}
@@ -14,14 +14,19 @@
 //! hot loops and generating efficient jump tables. The internal loops use fixed bounds and
 //! aligned memory access so the compiler can emit **AVX2**, **AVX‑512**, or **NEON** instructions
 //! when it decides they’re beneficial for a particular loop. The dispatch layer is also fully
-//! specialized at compile time to reduce branching and minimize overhead.
+//! specialized at compile time to reduce branching and minimize overhead. On x86, hand-written
+//! AVX2 and AVX‑512 kernels are also selected at runtime, so binaries built for older CPUs still
+//! use them where available (see [`Backend`]). Where cache-timing leaks matter, such as on shared
+//! hosts, [`Backend::ConstantTime`] replaces the S-box tables with bitsliced arithmetic; pass it
+//! to [`IceBuilder::backend`] and build an `Ice` or, with `build_fixed`, a fixed-level type.
 //!
-//! Supports ICE level 0 (Thin-ICE), 1 and 2.
+//! Supports ICE level 0 (Thin-ICE) and ICE-n for n in [1, 8] (see `IceLevel`).
 //!
 //! ### Dispatching Logic
 //! The library provides dispatching to balance latency and throughput:
 //! * **Serial Path**: Used for smaller buffers to avoid the overhead of thread synchronization.
-//! * **Parallel Path**: Utilizes Rayon for work-stealing parallelism on large data chunks (> 32 KB).
+//! * **Parallel Path**: Utilizes Rayon for work-stealing parallelism on large data chunks
+//!   (> 32 KB by default; see [`IceConfig`]).
 //! * **Tail Handling**: Recursive dispatch, using smaller block counts, is used to process the tail.
 //!
 //! ## Requirements
@@ -30,18 +35,66 @@
 //!   - `B` to be a power of two
 //!   - `data.len() >= B * 8` (where `8` is the BLOCK_SIZE constant)
 //!
+//! ## Features
+//! * **`std`** (default): Implies `alloc` and adds the [`tune`] calibration. Without it the crate
+//!   is `#![no_std]`.
+//! * **`alloc`**: The `Vec`-based padding API and `Eax::seal`/`Eax::open`.
+//! * **`parallel`** (default): Implies `std` and pulls in Rayon for the `_par` methods. Without it
+//!   the `_auto` methods always take the serial path.
+//! * **`rustcrypto`**, **`capi`**: See below.
+//!
 //! ## API Selection
 //!
 //! * **General Use**: Use `encrypt_auto` and `decrypt_auto`. Serial and parallel
 //!   processing is automatically selected based on buffer size and tail processing is handled.
 //! * **Serial Processing**: Use `encrypt` and `decrypt` to process serially with tail handling.
 //! * **Parallel Processing**: Use `encrypt_par` or `decrypt_par` to process in parallel with tail handling.
+//! * **Dedicated Thread Pools**: The `_par_in` and `_auto_in` variants run on a caller-supplied
+//!   `rayon::ThreadPool` instead of the global pool.
 //! * **High-Frequency Loops**: Use the `_chunks<B>` and `_chunks_par<B>` variants to bypass
 //!   dispatching and tail processing.
+//! * **Single Blocks**: Use `encrypt_block`, `encrypt_u64` or `encrypt_lr` (and their `decrypt_`
+//!   counterparts) for one 8-byte block without any dispatch overhead.
+//! * **Partial Blocks**: `encrypt_auto_with` and `decrypt_auto_with` take a `Residual` policy for
+//!   the trailing `len % 8` bytes, e.g. `Residual::LeavePlain` to match tools that leave them
+//!   unencrypted.
+//! * **Padding**: `encrypt_padded` and `decrypt_padded` (or the `_vec` variants) apply and strip
+//!   PKCS#7, ANSI X9.23, ISO 10126, ISO 7816-4 or zero padding.
+//! * **Out-of-Place**: The `_to` variants (`encrypt_to`, `decrypt_par_to`, `encrypt_auto_to`, ...)
+//!   read from a borrowed `&[u8]` and write to a separate destination of the same length.
+//! * **Untrusted Input**: The `try_` variants (`try_new`, `try_encrypt`, `try_decrypt_auto`, ...)
+//!   return an `IceError` instead of panicking, and treat empty input as a no-op.
+//! * **Fixed Levels**: `IceThin` and `Ice1` to `Ice8` take exact-size keys and fix the round count
+//!   at compile time so the Feistel rounds can be fully unrolled. They offer the same methods as `Ice`,
+//!   and `Ice::builder().build_fixed()` gives one its own tuning.
+//! * **RustCrypto**: With the `rustcrypto` feature, `IceThin`, `Ice1` to `Ice8` and `Ice` implement
+//!   the `cipher` crate's block cipher traits for use with generic mode crates.
+//! * **C Programs**: The `capi` feature exports the `ice.h` functions plus
+//!   `ice_key_encrypt_buffer`/`ice_key_decrypt_buffer`, and the `icefast-capi` crate builds them
+//!   into a C library; see the `capi` module.
+//!
+//! ## Modes of Operation
+//!
+//! The raw API is ECB. The [`modes`] module layers chaining modes on top of an `Ice` instance:
+//! * **CBC**: `modes::Cbc`, with parallel decryption.
+//! * **CTR**: `modes::Ctr`, seekable, with parallel keystream generation and no padding.
+//! * **Ciphertext Stealing**: `Ice::encrypt_ecb_cts` and `Ice::encrypt_cbc_cts` (CS1, CS2, CS3)
+//!   keep any input of at least 8 bytes at its exact length.
+//! * **Sectors**: `modes::Xts`, an XTS-style tweakable mode keyed by two `Ice` instances for
+//!   independently writable records, with parallel multi-sector processing.
+//! * **EAX**: `modes::Eax` authenticated encryption with associated data, using a detached or
+//!   appended tag.
+//! * **MACs**: `mac::Cmac` (OMAC1) and `mac::CbcMac` authenticate data incrementally, with
+//!   truncatable tags and constant-time verification.
+//! * **CFB / OFB / PCBC**: `modes::Cfb`, `modes::Cfb8`, `modes::Ofb` and `modes::Pcbc` for legacy
+//!   protocols. CFB decryption runs in parallel.
 //!
 //! ## Performance
 //! * **Benchmarks**: Run `cargo bench` to see performance comparisons between
 //!   auto-dispatch and manual parallelism.
+//! * **Tuning**: `Ice::builder()` sets the parallel threshold, batch sizes and thread pool per
+//!   instance, and `ICEFAST_*` environment variables override the defaults; see [`config`].
+//!   `tune::calibrate()` measures them per level on the current host as a loadable profile.
 //!
 //! ## Examples
 //!
@@ -56,6 +109,33 @@
 //! ice.decrypt_auto(&mut data);
 //! ```
 
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod backend;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod config;
+pub mod error;
 pub mod icefast;
+pub mod mac;
+pub mod modes;
+#[cfg(feature = "alloc")]
+pub mod padding;
+#[cfg(feature = "rustcrypto")]
+pub mod rustcrypto;
+#[cfg(feature = "std")]
+pub mod tune;
 
-pub use icefast::Ice;
+pub use backend::Backend;
+pub use config::{IceBuilder, IceConfig};
+pub use error::{IceError, PaddingError, ProfileError};
+pub use icefast::{
+    Ice, Ice1, Ice2, Ice3, Ice4, Ice5, Ice6, Ice7, Ice8, IceFixed, IceLevel, IceThin, Residual,
+};
+#[cfg(feature = "alloc")]
+pub use padding::Padding;
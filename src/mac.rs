@@ -0,0 +1,233 @@
+//! Message authentication codes over the 64-bit ICE block.
+//!
+//! [`Cmac`] is OMAC1 with the `0x1B` doubling constant for 64-bit blocks. [`CbcMac`] is the
+//! plain CBC-MAC of legacy systems, which is only secure for fixed-length messages. Both absorb
+//! input incrementally through `encrypt_u64`.
+
+use core::hint::black_box;
+use core::ops::Deref;
+
+use crate::icefast::BLOCK_SIZE;
+use crate::{Ice, IceError};
+
+/// An authentication tag of 1 to 8 bytes.
+///
+/// Compare tags with [`Cmac::verify`] or [`CbcMac::verify`], which run in constant time.
+#[derive(Clone, Copy, Debug)]
+pub struct Tag {
+    bytes: [u8; 8],
+    len: usize,
+}
+
+impl Tag {
+    /// Truncates the final MAC block to its `len` most significant bytes.
+    pub(crate) fn new(block: u64, len: usize) -> Self {
+        Self {
+            bytes: block.to_be_bytes(),
+            len,
+        }
+    }
+
+    /// Checks `tag` against this one in constant time.
+    pub(crate) fn verify(&self, tag: &[u8]) -> Result<(), IceError> {
+        if tag.len() != self.len {
+            return Err(IceError::TagMismatch);
+        }
+        let diff = self
+            .iter()
+            .zip(tag)
+            .fold(0u8, |acc, (a, b)| acc | black_box(a ^ b));
+        if black_box(diff) != 0 {
+            return Err(IceError::TagMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl Deref for Tag {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl AsRef<[u8]> for Tag {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+/// CMAC (OMAC1) over an [`Ice`] instance.
+#[derive(Clone, Debug)]
+pub struct Cmac<'a> {
+    chain: Chain<'a>,
+    k1: u64,
+    k2: u64,
+    tag_len: usize,
+}
+
+impl<'a> Cmac<'a> {
+    /// Start a CMAC computation producing full 8-byte tags.
+    pub fn new(ice: &'a Ice) -> Self {
+        Self::with_tag_len(ice, BLOCK_SIZE)
+    }
+
+    /// Start a CMAC computation producing tags of `tag_len` bytes.
+    ///
+    /// # Panics
+    /// Panics if `tag_len` is not in `1..=8`.
+    pub fn with_tag_len(ice: &'a Ice, tag_len: usize) -> Self {
+        assert!((1..=BLOCK_SIZE).contains(&tag_len));
+        let k1 = dbl(ice.encrypt_u64(0));
+        Self {
+            chain: Chain::new(ice),
+            k1,
+            k2: dbl(k1),
+            tag_len,
+        }
+    }
+
+    /// Absorbs `data` into the MAC.
+    pub fn update(&mut self, data: &[u8]) {
+        self.chain.update(data);
+    }
+
+    /// Returns the tag for everything absorbed so far.
+    pub fn finalize(self) -> Tag {
+        let Chain {
+            ice,
+            state,
+            mut buffer,
+            buffered,
+        } = self.chain;
+        let last = if buffered == BLOCK_SIZE {
+            u64::from_be_bytes(buffer) ^ self.k1
+        } else {
+            buffer[buffered] = 0x80;
+            u64::from_be_bytes(buffer) ^ self.k2
+        };
+        Tag::new(ice.encrypt_u64(state ^ last), self.tag_len)
+    }
+
+    /// Checks `tag` against the tag for everything absorbed so far, in constant time.
+    ///
+    /// # Errors
+    /// Returns [`IceError::TagMismatch`] if the tags differ in length or content.
+    pub fn verify(self, tag: &[u8]) -> Result<(), IceError> {
+        self.finalize().verify(tag)
+    }
+}
+
+/// Plain CBC-MAC with a zero IV over an [`Ice`] instance.
+///
+/// A partial final block is padded with zeros, and an empty message MACs as one zero block.
+/// Only use this where a legacy format requires it, and only for fixed-length messages;
+/// prefer [`Cmac`] otherwise.
+#[derive(Clone, Debug)]
+pub struct CbcMac<'a> {
+    chain: Chain<'a>,
+    tag_len: usize,
+}
+
+impl<'a> CbcMac<'a> {
+    /// Start a CBC-MAC computation producing full 8-byte tags.
+    pub fn new(ice: &'a Ice) -> Self {
+        Self::with_tag_len(ice, BLOCK_SIZE)
+    }
+
+    /// Start a CBC-MAC computation producing tags of `tag_len` bytes.
+    ///
+    /// # Panics
+    /// Panics if `tag_len` is not in `1..=8`.
+    pub fn with_tag_len(ice: &'a Ice, tag_len: usize) -> Self {
+        assert!((1..=BLOCK_SIZE).contains(&tag_len));
+        Self {
+            chain: Chain::new(ice),
+            tag_len,
+        }
+    }
+
+    /// Absorbs `data` into the MAC.
+    pub fn update(&mut self, data: &[u8]) {
+        self.chain.update(data);
+    }
+
+    /// Returns the tag for everything absorbed so far.
+    pub fn finalize(self) -> Tag {
+        let Chain {
+            ice, state, buffer, ..
+        } = self.chain;
+        // `buffer` is zeroed past `buffered`, which is the zero padding.
+        Tag::new(
+            ice.encrypt_u64(state ^ u64::from_be_bytes(buffer)),
+            self.tag_len,
+        )
+    }
+
+    /// Checks `tag` against the tag for everything absorbed so far, in constant time.
+    ///
+    /// # Errors
+    /// Returns [`IceError::TagMismatch`] if the tags differ in length or content.
+    pub fn verify(self, tag: &[u8]) -> Result<(), IceError> {
+        self.finalize().verify(tag)
+    }
+}
+
+/// CBC chaining that always holds back the last (possibly full) block for finalization.
+#[derive(Clone, Debug)]
+struct Chain<'a> {
+    ice: &'a Ice,
+    state: u64,
+    buffer: [u8; 8],
+    buffered: usize,
+}
+
+impl<'a> Chain<'a> {
+    fn new(ice: &'a Ice) -> Self {
+        Self {
+            ice,
+            state: 0,
+            buffer: [0; 8],
+            buffered: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+
+        // Top up the buffer; it is only absorbed once more input is known to follow.
+        let take = (BLOCK_SIZE - self.buffered).min(data.len());
+        self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+        self.buffered += take;
+        data = &data[take..];
+        if data.is_empty() {
+            return;
+        }
+
+        self.absorb(u64::from_be_bytes(self.buffer));
+        // Keep at least one byte back so the final block stays buffered.
+        let whole = (data.len() - 1) / BLOCK_SIZE * BLOCK_SIZE;
+        for block in data[..whole].chunks_exact(BLOCK_SIZE) {
+            self.absorb(u64::from_be_bytes(block.try_into().unwrap()));
+        }
+
+        let rest = &data[whole..];
+        self.buffer = [0; 8];
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    #[inline]
+    fn absorb(&mut self, block: u64) {
+        self.state = self.ice.encrypt_u64(self.state ^ block);
+    }
+}
+
+/// Doubles `x` in GF(2^64) modulo `x^64 + x^4 + x^3 + x + 1`.
+#[inline]
+pub(crate) fn dbl(x: u64) -> u64 {
+    (x << 1) ^ ((x >> 63) * 0x1B)
+}
@@ -0,0 +1,107 @@
+//! Block cipher modes of operation built on [`Ice`](crate::Ice).
+//!
+//! Each mode borrows an `Ice` and keeps its chaining state between calls, so a message can be
+//! processed in pieces. Modes report bad buffer lengths as an [`IceError`] rather than
+//! panicking.
+
+pub mod cbc;
+pub mod cfb;
+pub mod ctr;
+pub mod cts;
+pub mod eax;
+pub mod ofb;
+pub mod pcbc;
+pub mod xts;
+
+pub use cbc::Cbc;
+pub use cfb::{Cfb, Cfb8};
+pub use ctr::Ctr;
+pub use cts::CtsVariant;
+pub use eax::Eax;
+pub use ofb::Ofb;
+pub use pcbc::Pcbc;
+pub use xts::Xts;
+
+#[cfg(feature = "parallel")]
+use alloc::vec::Vec;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{IceConfig, IceError};
+
+/// Bytes processed per batch by the segmented mode kernels; also the parallel work unit.
+///
+/// Segments are staged in a stack buffer of this size, so it also bounds scratch space.
+pub(crate) const SEGMENT_SIZE: usize = 16_384;
+
+/// Calls `f(prev, segment)` for each `segment_size` piece of `data`, where `prev` is the last
+/// 8 bytes before the segment and `first_prev` for the first one.
+///
+/// Every `prev` is captured before any segment is processed, so `f` may overwrite its segment
+/// in place. Segments after the first must be at least 8 bytes long. With `parallel` set, the
+/// segments run in parallel on that configuration's pool.
+pub(crate) fn for_each_segment<F>(
+    data: &mut [u8],
+    segment_size: usize,
+    first_prev: [u8; 8],
+    parallel: Option<&IceConfig>,
+    f: F,
+) where
+    F: Fn([u8; 8], &mut [u8]) + Sync,
+{
+    #[cfg(feature = "parallel")]
+    if let Some(config) = parallel {
+        let segments = data.len().div_ceil(segment_size);
+        let prevs: Vec<[u8; 8]> = core::iter::once(first_prev)
+            .chain(
+                data.chunks(segment_size)
+                    .map(last_block)
+                    .take(segments.saturating_sub(1)),
+            )
+            .collect();
+        config.run_par(|| {
+            data.par_chunks_mut(segment_size)
+                .zip(prevs.par_iter())
+                .for_each(|(segment, prev)| f(*prev, segment))
+        });
+        return;
+    }
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallel;
+
+    let mut prev = first_prev;
+    for segment in data.chunks_mut(segment_size) {
+        let next = if segment.len() >= 8 {
+            last_block(segment)
+        } else {
+            prev
+        };
+        f(prev, segment);
+        prev = next;
+    }
+}
+
+/// XORs `src` into `dst`. Both slices must be the same length.
+#[inline]
+pub(crate) fn xor_in_place(dst: &mut [u8], src: &[u8]) {
+    debug_assert_eq!(dst.len(), src.len());
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d ^= s;
+    }
+}
+
+/// Returns the last block of a block-aligned, non-empty buffer.
+#[inline]
+pub(crate) fn last_block(data: &[u8]) -> [u8; 8] {
+    data[data.len() - 8..].try_into().unwrap()
+}
+
+pub(crate) fn check_same_len(src: &[u8], dst: &[u8]) -> Result<(), IceError> {
+    if src.len() != dst.len() {
+        return Err(IceError::LengthMismatch {
+            expected: src.len(),
+            actual: dst.len(),
+        });
+    }
+    Ok(())
+}
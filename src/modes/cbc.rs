@@ -0,0 +1,181 @@
+use super::{SEGMENT_SIZE, check_same_len, for_each_segment, last_block, xor_in_place};
+use crate::icefast::{BLOCK_SIZE, IceKernel, check_block_aligned};
+use crate::{Ice, IceError};
+
+/// Cipher block chaining over an [`Ice`] instance.
+///
+/// The IV carries over between calls, so a stream can be processed in block-aligned pieces.
+/// Encryption is inherently serial. Decryption of each block only depends on ciphertext, so the
+/// `_par` and `_auto` decryptors run the ECB kernel in parallel and XOR afterwards.
+///
+/// # Examples
+///
+/// ```rust
+/// use icefast::{Ice, modes::Cbc};
+///
+/// let ice = Ice::new(0, &[0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00]);
+/// let iv = [7u8; 8];
+///
+/// let mut data = vec![0u8; 1024];
+/// Cbc::new(&ice, iv).encrypt(&mut data).unwrap();
+/// Cbc::new(&ice, iv).decrypt_auto(&mut data).unwrap();
+/// assert_eq!(data, vec![0u8; 1024]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cbc<'a> {
+    ice: &'a Ice,
+    iv: [u8; 8],
+}
+
+impl<'a> Cbc<'a> {
+    /// Start a CBC stream with the initial vector `iv`.
+    pub fn new(ice: &'a Ice, iv: [u8; 8]) -> Self {
+        Self { ice, iv }
+    }
+
+    /// The current chaining value: the IV for the next call.
+    pub fn iv(&self) -> [u8; 8] {
+        self.iv
+    }
+
+    /// Encrypts the provided data in-place.
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+    pub fn encrypt(&mut self, data: &mut [u8]) -> Result<(), IceError> {
+        check_block_aligned(data)?;
+        let mut iv = u64::from_be_bytes(self.iv);
+        for block in data.chunks_exact_mut(BLOCK_SIZE) {
+            let p = u64::from_be_bytes(block.try_into().unwrap());
+            iv = self.ice.encrypt_u64(p ^ iv);
+            block.copy_from_slice(&iv.to_be_bytes());
+        }
+        self.iv = iv.to_be_bytes();
+        Ok(())
+    }
+
+    /// Encrypts `src` into `dst`, leaving `src` untouched.
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `src.len()` is not a multiple of 8, or
+    /// [`IceError::LengthMismatch`] if `dst.len() != src.len()`.
+    pub fn encrypt_to(&mut self, src: &[u8], dst: &mut [u8]) -> Result<(), IceError> {
+        check_block_aligned(src)?;
+        check_same_len(src, dst)?;
+        dst.copy_from_slice(src);
+        self.encrypt(dst)
+    }
+
+    /// Decrypts the provided data in-place.
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+    pub fn decrypt(&mut self, data: &mut [u8]) -> Result<(), IceError> {
+        self.decrypt_segments(data, false)
+    }
+
+    /// Decrypts the provided data in-place in parallel.
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+    #[cfg(feature = "parallel")]
+    pub fn decrypt_par(&mut self, data: &mut [u8]) -> Result<(), IceError> {
+        self.decrypt_segments(data, true)
+    }
+
+    /// Decrypts the provided data in-place.
+    ///
+    /// Switches between serial and parallel processing based on input length and the instance's
+    /// [`IceConfig`](crate::IceConfig).
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+    pub fn decrypt_auto(&mut self, data: &mut [u8]) -> Result<(), IceError> {
+        self.decrypt_segments(data, self.ice.config().auto_parallel(data.len()))
+    }
+
+    /// Decrypts `src` into `dst`, leaving `src` untouched.
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `src.len()` is not a multiple of 8, or
+    /// [`IceError::LengthMismatch`] if `dst.len() != src.len()`.
+    pub fn decrypt_to(&mut self, src: &[u8], dst: &mut [u8]) -> Result<(), IceError> {
+        if self.check_to(src, dst)? {
+            self.ice.dispatch_serial_to::<true>(src, dst);
+            self.unchain(src, dst);
+        }
+        Ok(())
+    }
+
+    /// Decrypts `src` into `dst` in parallel, leaving `src` untouched.
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `src.len()` is not a multiple of 8, or
+    /// [`IceError::LengthMismatch`] if `dst.len() != src.len()`.
+    #[cfg(feature = "parallel")]
+    pub fn decrypt_par_to(&mut self, src: &[u8], dst: &mut [u8]) -> Result<(), IceError> {
+        if self.check_to(src, dst)? {
+            self.ice
+                .config()
+                .run_par(|| self.ice.dispatch_par_to::<true>(src, dst));
+            self.unchain(src, dst);
+        }
+        Ok(())
+    }
+
+    /// Decrypts `src` into `dst`, leaving `src` untouched.
+    ///
+    /// Switches between serial and parallel processing based on input length and the instance's
+    /// [`IceConfig`](crate::IceConfig).
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `src.len()` is not a multiple of 8, or
+    /// [`IceError::LengthMismatch`] if `dst.len() != src.len()`.
+    pub fn decrypt_auto_to(&mut self, src: &[u8], dst: &mut [u8]) -> Result<(), IceError> {
+        if self.check_to(src, dst)? {
+            self.ice.dispatch_auto_to::<true>(src, dst);
+            self.unchain(src, dst);
+        }
+        Ok(())
+    }
+
+    fn check_to(&self, src: &[u8], dst: &[u8]) -> Result<bool, IceError> {
+        let non_empty = check_block_aligned(src)?;
+        check_same_len(src, dst)?;
+        Ok(non_empty)
+    }
+
+    /// XORs ECB-decrypted `dst` with the ciphertext shifted by one block and advances the IV.
+    fn unchain(&mut self, src: &[u8], dst: &mut [u8]) {
+        let len = src.len();
+        xor_in_place(&mut dst[..BLOCK_SIZE], &self.iv);
+        xor_in_place(&mut dst[BLOCK_SIZE..], &src[..len - BLOCK_SIZE]);
+        self.iv = last_block(src);
+    }
+
+    /// In-place decryption stages each segment's ciphertext on the stack before overwriting it.
+    fn decrypt_segments(&mut self, data: &mut [u8], parallel: bool) -> Result<(), IceError> {
+        if !check_block_aligned(data)? {
+            return Ok(());
+        }
+        let next_iv = last_block(data);
+        let parallel = parallel.then(|| self.ice.config());
+        for_each_segment(data, SEGMENT_SIZE, self.iv, parallel, |prev, segment| {
+            self.decrypt_segment(prev, segment)
+        });
+        self.iv = next_iv;
+        Ok(())
+    }
+
+    fn decrypt_segment(&self, prev: [u8; 8], segment: &mut [u8]) {
+        let mut scratch = [0u8; SEGMENT_SIZE];
+        let ciphertext = &mut scratch[..segment.len()];
+        ciphertext.copy_from_slice(segment);
+
+        self.ice.dispatch_serial_to::<true>(ciphertext, segment);
+
+        let len = segment.len();
+        xor_in_place(&mut segment[..BLOCK_SIZE], &prev);
+        xor_in_place(&mut segment[BLOCK_SIZE..], &ciphertext[..len - BLOCK_SIZE]);
+    }
+}
@@ -0,0 +1,203 @@
+use super::{SEGMENT_SIZE, for_each_segment, last_block, xor_in_place};
+use crate::Ice;
+use crate::icefast::{BLOCK_SIZE, IceKernel};
+
+/// Bytes per CFB-8 segment; each byte needs a full block of staged cipher input.
+const CFB8_SEGMENT_SIZE: usize = SEGMENT_SIZE / BLOCK_SIZE;
+
+/// 64-bit cipher feedback over an [`Ice`] instance.
+///
+/// Messages of any length are supported and may be fed across many calls; a partial block is
+/// carried over to the next call. Encryption is serial. Decryption of whole blocks only
+/// depends on ciphertext, so the `_par` and `_auto` decryptors encrypt the shifted ciphertext
+/// through the batched ECB kernel in parallel.
+#[derive(Clone, Debug)]
+pub struct Cfb<'a> {
+    ice: &'a Ice,
+    /// The cipher input for the next block: the previous ciphertext block, filled in as the
+    /// current block's ciphertext is produced.
+    register: [u8; 8],
+    keystream: [u8; 8],
+    /// Bytes of the current block processed; 8 at a block boundary.
+    pos: usize,
+}
+
+impl<'a> Cfb<'a> {
+    /// Start a CFB stream with the initial vector `iv`.
+    pub fn new(ice: &'a Ice, iv: [u8; 8]) -> Self {
+        Self {
+            ice,
+            register: iv,
+            keystream: [0; 8],
+            pos: BLOCK_SIZE,
+        }
+    }
+
+    /// Encrypts the provided data in-place.
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        for byte in data {
+            self.refill();
+            *byte ^= self.keystream[self.pos];
+            self.register[self.pos] = *byte;
+            self.pos += 1;
+        }
+    }
+
+    /// Decrypts the provided data in-place.
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        self.decrypt_with(data, false);
+    }
+
+    /// Decrypts the provided data in-place in parallel.
+    #[cfg(feature = "parallel")]
+    pub fn decrypt_par(&mut self, data: &mut [u8]) {
+        self.decrypt_with(data, true);
+    }
+
+    /// Decrypts the provided data in-place.
+    ///
+    /// Switches between serial and parallel processing based on input length and the instance's
+    /// [`IceConfig`](crate::IceConfig).
+    pub fn decrypt_auto(&mut self, data: &mut [u8]) {
+        self.decrypt_with(data, self.ice.config().auto_parallel(data.len()));
+    }
+
+    fn refill(&mut self) {
+        if self.pos == BLOCK_SIZE {
+            self.keystream = self.ice.encrypt_block(self.register);
+            self.pos = 0;
+        }
+    }
+
+    fn decrypt_bytes(&mut self, data: &mut [u8]) {
+        for byte in data {
+            self.refill();
+            let c = *byte;
+            *byte ^= self.keystream[self.pos];
+            self.register[self.pos] = c;
+            self.pos += 1;
+        }
+    }
+
+    fn decrypt_with(&mut self, data: &mut [u8], parallel: bool) {
+        // Finish any partial block, then handle whole blocks in batches.
+        let head_len = ((BLOCK_SIZE - self.pos) % BLOCK_SIZE).min(data.len());
+        let (head, rest) = data.split_at_mut(head_len);
+        self.decrypt_bytes(head);
+
+        let (body, tail) = rest.split_at_mut(rest.len() / BLOCK_SIZE * BLOCK_SIZE);
+        if !body.is_empty() {
+            let next = last_block(body);
+            for_each_segment(
+                body,
+                SEGMENT_SIZE,
+                self.register,
+                parallel.then(|| self.ice.config()),
+                |prev, segment| self.decrypt_segment(prev, segment),
+            );
+            self.register = next;
+        }
+
+        self.decrypt_bytes(tail);
+    }
+
+    fn decrypt_segment(&self, prev: [u8; 8], segment: &mut [u8]) {
+        let len = segment.len();
+        let mut scratch = [0u8; SEGMENT_SIZE];
+        let keystream = &mut scratch[..len];
+        keystream[..BLOCK_SIZE].copy_from_slice(&prev);
+        keystream[BLOCK_SIZE..].copy_from_slice(&segment[..len - BLOCK_SIZE]);
+
+        self.ice.dispatch_serial::<false>(keystream);
+        xor_in_place(segment, keystream);
+    }
+}
+
+/// 8-bit cipher feedback over an [`Ice`] instance.
+///
+/// Each byte costs one block encryption. Encryption is serial; decryption stages the cipher
+/// input of many bytes at once and runs it through the batched ECB kernel, in parallel for
+/// the `_par` and `_auto` decryptors.
+#[derive(Clone, Debug)]
+pub struct Cfb8<'a> {
+    ice: &'a Ice,
+    /// The last 8 ciphertext bytes, starting from the IV.
+    register: [u8; 8],
+}
+
+impl<'a> Cfb8<'a> {
+    /// Start a CFB-8 stream with the initial vector `iv`.
+    pub fn new(ice: &'a Ice, iv: [u8; 8]) -> Self {
+        Self { ice, register: iv }
+    }
+
+    /// Encrypts the provided data in-place.
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        let mut register = u64::from_be_bytes(self.register);
+        for byte in data {
+            *byte ^= (self.ice.encrypt_u64(register) >> 56) as u8;
+            register = (register << 8) | *byte as u64;
+        }
+        self.register = register.to_be_bytes();
+    }
+
+    /// Decrypts the provided data in-place.
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        self.decrypt_with(data, false);
+    }
+
+    /// Decrypts the provided data in-place in parallel.
+    #[cfg(feature = "parallel")]
+    pub fn decrypt_par(&mut self, data: &mut [u8]) {
+        self.decrypt_with(data, true);
+    }
+
+    /// Decrypts the provided data in-place.
+    ///
+    /// Switches between serial and parallel processing based on input length and the instance's
+    /// [`IceConfig`](crate::IceConfig).
+    pub fn decrypt_auto(&mut self, data: &mut [u8]) {
+        self.decrypt_with(data, self.ice.config().auto_parallel(data.len()));
+    }
+
+    fn decrypt_with(&mut self, data: &mut [u8], parallel: bool) {
+        let next = shift_in(self.register, data);
+        for_each_segment(
+            data,
+            CFB8_SEGMENT_SIZE,
+            self.register,
+            parallel.then(|| self.ice.config()),
+            |prev, segment| self.decrypt_segment(prev, segment),
+        );
+        self.register = next;
+    }
+
+    fn decrypt_segment(&self, prev: [u8; 8], segment: &mut [u8]) {
+        let len = segment.len();
+        let mut scratch = [0u8; SEGMENT_SIZE];
+        let inputs = &mut scratch[..len * BLOCK_SIZE];
+
+        // The cipher input for byte `i` is the 8 ciphertext bytes before it.
+        let mut register = u64::from_be_bytes(prev);
+        for (input, &c) in inputs.chunks_exact_mut(BLOCK_SIZE).zip(segment.iter()) {
+            input.copy_from_slice(&register.to_be_bytes());
+            register = (register << 8) | c as u64;
+        }
+
+        self.ice.dispatch_serial::<false>(inputs);
+        for (byte, keystream) in segment.iter_mut().zip(inputs.chunks_exact(BLOCK_SIZE)) {
+            *byte ^= keystream[0];
+        }
+    }
+}
+
+/// The feedback register after shifting in `data`.
+fn shift_in(register: [u8; 8], data: &[u8]) -> [u8; 8] {
+    if data.len() >= BLOCK_SIZE {
+        return last_block(data);
+    }
+    let mut next = [0u8; 8];
+    next[..BLOCK_SIZE - data.len()].copy_from_slice(&register[data.len()..]);
+    next[BLOCK_SIZE - data.len()..].copy_from_slice(data);
+    next
+}
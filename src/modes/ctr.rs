@@ -0,0 +1,184 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::{SEGMENT_SIZE, xor_in_place};
+use crate::icefast::{BLOCK_SIZE, IceKernel};
+use crate::{Ice, IceError};
+
+const SEGMENT_BLOCKS: u64 = (SEGMENT_SIZE / BLOCK_SIZE) as u64;
+
+/// Counter mode over an [`Ice`] instance.
+///
+/// The 64-bit counter block is split into a fixed nonce in the high bits and a counter in the
+/// low `counter_bits`, which wraps within its field. Using more than `2^counter_bits` blocks
+/// would repeat the keystream and is reported as [`IceError::CounterOverflow`].
+///
+/// Every counter block is independent, so keystream is produced in batches through the ECB
+/// kernel and in parallel for large buffers. Messages of any length are supported, and
+/// [`Ctr::seek`] allows random access.
+///
+/// # Examples
+///
+/// ```rust
+/// use icefast::{Ice, modes::Ctr};
+///
+/// let ice = Ice::new(0, &[0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00]);
+///
+/// let mut data = b"any length at all".to_vec();
+/// Ctr::with_split(&ice, 0xC0FFEE, 32, 0).apply_keystream(&mut data).unwrap();
+///
+/// let mut ctr = Ctr::with_split(&ice, 0xC0FFEE, 32, 0);
+/// ctr.apply_keystream(&mut data).unwrap();
+/// assert_eq!(data, b"any length at all");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Ctr<'a> {
+    ice: &'a Ice,
+    nonce: u64,
+    counter_mask: u64,
+    initial: u64,
+    /// Index of the next keystream block, counted from `initial`. It is wide enough to reach
+    /// `2^64` when the whole 64-bit counter space has been used, so it never wraps.
+    block: u128,
+    keystream: [u8; 8],
+    /// Bytes of `keystream` already used; 8 when nothing is buffered.
+    keystream_pos: usize,
+}
+
+impl<'a> Ctr<'a> {
+    /// Start a counter stream that uses the whole of `iv` as the initial counter block.
+    pub fn new(ice: &'a Ice, iv: [u8; 8]) -> Self {
+        Self::with_split(ice, 0, 64, u64::from_be_bytes(iv))
+    }
+
+    /// Start a counter stream with `nonce` in the high `64 - counter_bits` bits of the counter
+    /// block and `initial_counter` in the low `counter_bits`.
+    ///
+    /// # Panics
+    /// Panics if `counter_bits` is not in the range [1, 64] or either value does not fit in
+    /// its field.
+    pub fn with_split(ice: &'a Ice, nonce: u64, counter_bits: u32, initial_counter: u64) -> Self {
+        assert!(
+            (1..=64).contains(&counter_bits),
+            "counter_bits must be in the range [1, 64]"
+        );
+        assert!(
+            nonce >> (64 - counter_bits) == 0,
+            "nonce does not fit in its field"
+        );
+        let counter_mask = u64::MAX >> (64 - counter_bits);
+        let nonce = nonce.checked_shl(counter_bits).unwrap_or(0);
+        assert!(
+            initial_counter <= counter_mask,
+            "initial counter does not fit in its field"
+        );
+        Self {
+            ice,
+            nonce,
+            counter_mask,
+            initial: initial_counter,
+            block: 0,
+            keystream: [0; 8],
+            keystream_pos: BLOCK_SIZE,
+        }
+    }
+
+    /// Position the stream at the start of keystream block `block_index`.
+    pub fn seek(&mut self, block_index: u64) {
+        self.block = block_index.into();
+        self.keystream_pos = BLOCK_SIZE;
+    }
+
+    /// Current position in bytes from the start of the keystream.
+    pub fn position(&self) -> u128 {
+        let buffered = (BLOCK_SIZE - self.keystream_pos) as u128;
+        self.block * BLOCK_SIZE as u128 - buffered
+    }
+
+    /// XORs the keystream into `data`, continuing from the current position.
+    ///
+    /// Switches between serial and parallel keystream generation based on input length and the
+    /// instance's [`IceConfig`](crate::IceConfig).
+    ///
+    /// # Errors
+    /// Returns [`IceError::CounterOverflow`] if `data` would run past the end of the counter
+    /// space; `data` and the stream position are left unchanged.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) -> Result<(), IceError> {
+        let buffered = BLOCK_SIZE - self.keystream_pos;
+        let needed = data.len().saturating_sub(buffered).div_ceil(BLOCK_SIZE) as u128;
+        if self.block + needed > self.counter_mask as u128 + 1 {
+            return Err(IceError::CounterOverflow);
+        }
+
+        let (head, rest) = data.split_at_mut(buffered.min(data.len()));
+        xor_in_place(head, &self.keystream[self.keystream_pos..][..head.len()]);
+        self.keystream_pos += head.len();
+
+        let (body, tail) = rest.split_at_mut(rest.len() / BLOCK_SIZE * BLOCK_SIZE);
+        if !body.is_empty() {
+            #[cfg(feature = "parallel")]
+            if self.ice.config().auto_parallel(body.len()) {
+                self.ice.config().run_par(|| self.xor_blocks_par(body));
+            } else {
+                self.xor_blocks(body);
+            }
+            #[cfg(not(feature = "parallel"))]
+            self.xor_blocks(body);
+            self.block += (body.len() / BLOCK_SIZE) as u128;
+        }
+
+        if !tail.is_empty() {
+            self.keystream = self.keystream_block(self.next_block());
+            self.block += 1;
+            xor_in_place(tail, &self.keystream[..tail.len()]);
+            self.keystream_pos = tail.len();
+        }
+        Ok(())
+    }
+
+    /// The next block index as the counter field sees it; only its low `counter_bits` matter.
+    fn next_block(&self) -> u64 {
+        self.block as u64
+    }
+
+    fn counter_block(&self, block: u64) -> u64 {
+        self.nonce | (self.initial.wrapping_add(block) & self.counter_mask)
+    }
+
+    fn keystream_block(&self, block: u64) -> [u8; 8] {
+        self.ice
+            .encrypt_u64(self.counter_block(block))
+            .to_be_bytes()
+    }
+
+    fn xor_blocks(&self, data: &mut [u8]) {
+        for (i, segment) in data.chunks_mut(SEGMENT_SIZE).enumerate() {
+            self.xor_segment(
+                self.next_block().wrapping_add(i as u64 * SEGMENT_BLOCKS),
+                segment,
+            );
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    fn xor_blocks_par(&self, data: &mut [u8]) {
+        data.par_chunks_mut(SEGMENT_SIZE)
+            .enumerate()
+            .for_each(|(i, segment)| {
+                let first_block = self.next_block().wrapping_add(i as u64 * SEGMENT_BLOCKS);
+                self.xor_segment(first_block, segment)
+            });
+    }
+
+    /// Encrypts the counter blocks for a block-aligned segment as one batch and XORs them in.
+    fn xor_segment(&self, first_block: u64, segment: &mut [u8]) {
+        let mut scratch = [0u8; SEGMENT_SIZE];
+        let keystream = &mut scratch[..segment.len()];
+        for (i, block) in keystream.chunks_exact_mut(BLOCK_SIZE).enumerate() {
+            let counter = self.counter_block(first_block.wrapping_add(i as u64));
+            block.copy_from_slice(&counter.to_be_bytes());
+        }
+        self.ice.dispatch_serial::<false>(keystream);
+        xor_in_place(segment, keystream);
+    }
+}
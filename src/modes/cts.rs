@@ -0,0 +1,206 @@
+use super::{Cbc, last_block};
+use crate::icefast::{BLOCK_SIZE, IceKernel};
+use crate::{Ice, IceError};
+
+/// Ciphertext stealing layout, as named in NIST SP 800-38A Addendum.
+///
+/// The variants only differ in the order of the last two ciphertext blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CtsVariant {
+    /// The truncated next-to-last block precedes the final full block.
+    Cs1,
+    /// As `Cs1` when the input is block-aligned, otherwise as `Cs3`.
+    Cs2,
+    /// The final full block always precedes the (possibly truncated) next-to-last block.
+    Cs3,
+}
+
+impl CtsVariant {
+    /// Whether the last two blocks are swapped for an input with `residual` trailing bytes.
+    fn swaps(self, residual: usize) -> bool {
+        match self {
+            CtsVariant::Cs1 => false,
+            CtsVariant::Cs2 => residual != 0,
+            CtsVariant::Cs3 => true,
+        }
+    }
+}
+
+/// Ciphertext stealing entry points.
+///
+/// These accept any length of at least one block and produce output of the same length. All
+/// but the last two blocks go through the usual auto-dispatched paths.
+impl Ice {
+    /// Encrypts the provided data in-place with ECB ciphertext stealing.
+    ///
+    /// # Errors
+    /// Returns [`IceError::EmptyInput`] or [`IceError::InputTooShort`] if `data` is shorter
+    /// than one block.
+    pub fn encrypt_ecb_cts(&self, data: &mut [u8], variant: CtsVariant) -> Result<(), IceError> {
+        let (full, d) = cts_split(data)?;
+        if d == 0 {
+            self.dispatch_auto::<false>(data);
+            swap_last_blocks(data, variant, d);
+            return Ok(());
+        }
+
+        let (head, tail) = data.split_at_mut(full - BLOCK_SIZE);
+        if !head.is_empty() {
+            self.dispatch_auto::<false>(head);
+        }
+
+        // X = E(P[n-1]); C[n] = E(P[n]* || X[d..]); C[n-1]* = X[..d]
+        let x = self.encrypt_block(tail[..BLOCK_SIZE].try_into().unwrap());
+        let mut last = x;
+        last[..d].copy_from_slice(&tail[BLOCK_SIZE..]);
+        let c_n = self.encrypt_block(last);
+
+        store_stolen(tail, &x[..d], &c_n, variant);
+        Ok(())
+    }
+
+    /// Decrypts the provided data in-place with ECB ciphertext stealing.
+    ///
+    /// # Errors
+    /// Returns [`IceError::EmptyInput`] or [`IceError::InputTooShort`] if `data` is shorter
+    /// than one block.
+    pub fn decrypt_ecb_cts(&self, data: &mut [u8], variant: CtsVariant) -> Result<(), IceError> {
+        let (full, d) = cts_split(data)?;
+        if d == 0 {
+            swap_last_blocks(data, variant, d);
+            self.dispatch_auto::<true>(data);
+            return Ok(());
+        }
+
+        let (head, tail) = data.split_at_mut(full - BLOCK_SIZE);
+        if !head.is_empty() {
+            self.dispatch_auto::<true>(head);
+        }
+
+        let (stolen, c_n) = load_stolen(tail, d, variant);
+        // D(C[n]) = P[n]* || X[d..], and X = C[n-1]* || X[d..] = E(P[n-1])
+        let z = self.decrypt_block(c_n);
+        let mut x = z;
+        x[..d].copy_from_slice(&stolen[..d]);
+
+        tail[..BLOCK_SIZE].copy_from_slice(&self.decrypt_block(x));
+        tail[BLOCK_SIZE..].copy_from_slice(&z[..d]);
+        Ok(())
+    }
+
+    /// Encrypts the provided data in-place with CBC ciphertext stealing.
+    ///
+    /// # Errors
+    /// Returns [`IceError::EmptyInput`] or [`IceError::InputTooShort`] if `data` is shorter
+    /// than one block.
+    pub fn encrypt_cbc_cts(
+        &self,
+        iv: [u8; 8],
+        data: &mut [u8],
+        variant: CtsVariant,
+    ) -> Result<(), IceError> {
+        let (full, d) = cts_split(data)?;
+        Cbc::new(self, iv).encrypt(&mut data[..full])?;
+        if d == 0 {
+            swap_last_blocks(data, variant, d);
+            return Ok(());
+        }
+
+        // C[n] = E((P[n]* || 0) ^ C[n-1]); C[n-1]* = C[n-1][..d]
+        let tail = &mut data[full - BLOCK_SIZE..];
+        let c_prev = last_block(&tail[..BLOCK_SIZE]);
+        let mut last = c_prev;
+        for (l, p) in last.iter_mut().zip(&tail[BLOCK_SIZE..]) {
+            *l ^= p;
+        }
+        let c_n = self.encrypt_block(last);
+
+        store_stolen(tail, &c_prev[..d], &c_n, variant);
+        Ok(())
+    }
+
+    /// Decrypts the provided data in-place with CBC ciphertext stealing.
+    ///
+    /// # Errors
+    /// Returns [`IceError::EmptyInput`] or [`IceError::InputTooShort`] if `data` is shorter
+    /// than one block.
+    pub fn decrypt_cbc_cts(
+        &self,
+        iv: [u8; 8],
+        data: &mut [u8],
+        variant: CtsVariant,
+    ) -> Result<(), IceError> {
+        let (full, d) = cts_split(data)?;
+        if d == 0 {
+            swap_last_blocks(data, variant, d);
+            return Cbc::new(self, iv).decrypt_auto(data);
+        }
+
+        let (head, tail) = data.split_at_mut(full - BLOCK_SIZE);
+        let mut cbc = Cbc::new(self, iv);
+        cbc.decrypt_auto(head)?;
+        let c_prev_prev = u64::from_be_bytes(cbc.iv());
+
+        // D(C[n]) = (P[n]* || 0) ^ C[n-1], so the stolen bytes of C[n-1] are its tail.
+        let (stolen, c_n) = load_stolen(tail, d, variant);
+        let z = self.decrypt_block(c_n);
+        let mut c_prev = z;
+        c_prev[..d].copy_from_slice(&stolen[..d]);
+
+        let p_prev = self.decrypt_u64(u64::from_be_bytes(c_prev)) ^ c_prev_prev;
+        tail[..BLOCK_SIZE].copy_from_slice(&p_prev.to_be_bytes());
+        for (i, p) in tail[BLOCK_SIZE..].iter_mut().enumerate() {
+            *p = z[i] ^ c_prev[i];
+        }
+        Ok(())
+    }
+}
+
+/// Splits a CTS input length into the block-aligned part and the residual byte count.
+fn cts_split(data: &[u8]) -> Result<(usize, usize), IceError> {
+    match data.len() {
+        0 => Err(IceError::EmptyInput),
+        len if len < BLOCK_SIZE => Err(IceError::InputTooShort {
+            min: BLOCK_SIZE,
+            actual: len,
+        }),
+        len => Ok((len / BLOCK_SIZE * BLOCK_SIZE, len % BLOCK_SIZE)),
+    }
+}
+
+/// Swaps the last two blocks of block-aligned data if the variant calls for it.
+fn swap_last_blocks(data: &mut [u8], variant: CtsVariant, residual: usize) {
+    let len = data.len();
+    if variant.swaps(residual) && len >= 2 * BLOCK_SIZE {
+        let (a, b) = data[len - 2 * BLOCK_SIZE..].split_at_mut(BLOCK_SIZE);
+        a.swap_with_slice(b);
+    }
+}
+
+/// Writes the truncated `stolen` block and the full final block `c_n` to the last
+/// `8 + stolen.len()` bytes in the variant's order.
+fn store_stolen(tail: &mut [u8], stolen: &[u8], c_n: &[u8; 8], variant: CtsVariant) {
+    let d = stolen.len();
+    if variant.swaps(d) {
+        tail[..BLOCK_SIZE].copy_from_slice(c_n);
+        tail[BLOCK_SIZE..].copy_from_slice(stolen);
+    } else {
+        tail[..d].copy_from_slice(stolen);
+        tail[d..].copy_from_slice(c_n);
+    }
+}
+
+/// Reads back the truncated block (zero-extended) and the full final block written by
+/// [`store_stolen`].
+fn load_stolen(tail: &[u8], d: usize, variant: CtsVariant) -> ([u8; 8], [u8; 8]) {
+    let mut stolen = [0u8; 8];
+    let c_n;
+    if variant.swaps(d) {
+        c_n = last_block(&tail[..BLOCK_SIZE]);
+        stolen[..d].copy_from_slice(&tail[BLOCK_SIZE..]);
+    } else {
+        stolen[..d].copy_from_slice(&tail[..d]);
+        c_n = last_block(&tail[d..]);
+    }
+    (stolen, c_n)
+}
@@ -0,0 +1,124 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::Ctr;
+use crate::icefast::BLOCK_SIZE;
+use crate::mac::{Cmac, Tag};
+use crate::{Ice, IceError};
+
+/// EAX authenticated encryption over an [`Ice`] instance.
+///
+/// The nonce and associated data are authenticated with CMAC, and the message is encrypted
+/// with [`Ctr`] using a full 64-bit counter, so its keystream is produced in batches and in
+/// parallel for large buffers. A nonce must never be reused with the same key.
+///
+/// # Examples
+///
+/// ```rust
+/// use icefast::{Ice, modes::Eax};
+///
+/// let ice = Ice::new(1, &[0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00]);
+/// let eax = Eax::new(&ice);
+///
+/// let mut data = *b"telemetry";
+/// let tag = eax.seal_detached(b"nonce 1", b"header", &mut data);
+/// eax.open_detached(b"nonce 1", b"header", &mut data, &tag).unwrap();
+/// assert_eq!(&data, b"telemetry");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Eax<'a> {
+    ice: &'a Ice,
+    tag_len: usize,
+}
+
+impl<'a> Eax<'a> {
+    /// Creates an EAX instance producing full 8-byte tags.
+    pub fn new(ice: &'a Ice) -> Self {
+        Self::with_tag_len(ice, BLOCK_SIZE)
+    }
+
+    /// Creates an EAX instance producing tags of `tag_len` bytes.
+    ///
+    /// # Panics
+    /// Panics if `tag_len` is not in `1..=8`.
+    pub fn with_tag_len(ice: &'a Ice, tag_len: usize) -> Self {
+        assert!((1..=BLOCK_SIZE).contains(&tag_len));
+        Self { ice, tag_len }
+    }
+
+    /// The tag length in bytes.
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Encrypts `data` in-place and returns its tag.
+    pub fn seal_detached(&self, nonce: &[u8], ad: &[u8], data: &mut [u8]) -> Tag {
+        let cmac = Cmac::new(self.ice);
+        let n = omac(&cmac, 0, nonce);
+        let h = omac(&cmac, 1, ad);
+        self.ctr(n, data);
+        let c = omac(&cmac, 2, data);
+        Tag::new(n ^ h ^ c, self.tag_len)
+    }
+
+    /// Encrypts `data` in-place and appends its tag.
+    #[cfg(feature = "alloc")]
+    pub fn seal(&self, nonce: &[u8], ad: &[u8], data: &mut Vec<u8>) {
+        let tag = self.seal_detached(nonce, ad, data);
+        data.extend_from_slice(&tag);
+    }
+
+    /// Authenticates `data` against `tag` and decrypts it in-place.
+    ///
+    /// # Errors
+    /// Returns [`IceError::TagMismatch`] without touching `data` if authentication fails.
+    pub fn open_detached(
+        &self,
+        nonce: &[u8],
+        ad: &[u8],
+        data: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), IceError> {
+        let cmac = Cmac::new(self.ice);
+        let n = omac(&cmac, 0, nonce);
+        let h = omac(&cmac, 1, ad);
+        let c = omac(&cmac, 2, data);
+        Tag::new(n ^ h ^ c, self.tag_len).verify(tag)?;
+        self.ctr(n, data);
+        Ok(())
+    }
+
+    /// Authenticates and decrypts `data` in-place, where the tag is its last `tag_len` bytes,
+    /// and removes the tag.
+    ///
+    /// # Errors
+    /// Returns [`IceError::InputTooShort`] if `data` cannot hold a tag, or
+    /// [`IceError::TagMismatch`] if authentication fails. `data` is untouched in either case.
+    #[cfg(feature = "alloc")]
+    pub fn open(&self, nonce: &[u8], ad: &[u8], data: &mut Vec<u8>) -> Result<(), IceError> {
+        let Some(len) = data.len().checked_sub(self.tag_len) else {
+            return Err(IceError::InputTooShort {
+                min: self.tag_len,
+                actual: data.len(),
+            });
+        };
+        let (message, tag) = data.split_at_mut(len);
+        self.open_detached(nonce, ad, message, tag)?;
+        data.truncate(len);
+        Ok(())
+    }
+
+    fn ctr(&self, n: u64, data: &mut [u8]) {
+        Ctr::new(self.ice, n.to_be_bytes())
+            .apply_keystream(data)
+            .expect("a 64-bit counter covers any slice");
+    }
+}
+
+/// `OMAC^t(data)`: CMAC of the block `t` (zero padded on the left) followed by `data`.
+fn omac(cmac: &Cmac<'_>, t: u8, data: &[u8]) -> u64 {
+    let mut cmac = cmac.clone();
+    cmac.update(&u64::from(t).to_be_bytes());
+    cmac.update(data);
+    u64::from_be_bytes((*cmac.finalize()).try_into().unwrap())
+}
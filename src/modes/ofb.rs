@@ -0,0 +1,39 @@
+use crate::Ice;
+use crate::icefast::BLOCK_SIZE;
+
+/// Output feedback over an [`Ice`] instance.
+///
+/// The keystream is the IV encrypted repeatedly, so it is generated serially. Messages of any
+/// length are supported and may be fed across many calls. Encryption and decryption are the
+/// same operation.
+#[derive(Clone, Debug)]
+pub struct Ofb<'a> {
+    ice: &'a Ice,
+    /// The current keystream block, starting from the IV.
+    register: [u8; 8],
+    /// Bytes of `register` used as keystream; 8 when the next block is needed.
+    pos: usize,
+}
+
+impl<'a> Ofb<'a> {
+    /// Start an OFB stream with the initial vector `iv`.
+    pub fn new(ice: &'a Ice, iv: [u8; 8]) -> Self {
+        Self {
+            ice,
+            register: iv,
+            pos: BLOCK_SIZE,
+        }
+    }
+
+    /// XORs the keystream into `data`, continuing from the current position.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data {
+            if self.pos == BLOCK_SIZE {
+                self.register = self.ice.encrypt_block(self.register);
+                self.pos = 0;
+            }
+            *byte ^= self.register[self.pos];
+            self.pos += 1;
+        }
+    }
+}
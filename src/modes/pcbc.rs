@@ -0,0 +1,58 @@
+use crate::icefast::{BLOCK_SIZE, check_block_aligned};
+use crate::{Ice, IceError};
+
+/// Propagating cipher block chaining over an [`Ice`] instance.
+///
+/// Each block is chained with both the previous plaintext and ciphertext, so encryption and
+/// decryption are serial. The chaining value carries over between calls.
+#[derive(Clone, Debug)]
+pub struct Pcbc<'a> {
+    ice: &'a Ice,
+    iv: [u8; 8],
+}
+
+impl<'a> Pcbc<'a> {
+    /// Start a PCBC stream with the initial vector `iv`.
+    pub fn new(ice: &'a Ice, iv: [u8; 8]) -> Self {
+        Self { ice, iv }
+    }
+
+    /// The current chaining value: the IV for the next call.
+    pub fn iv(&self) -> [u8; 8] {
+        self.iv
+    }
+
+    /// Encrypts the provided data in-place.
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+    pub fn encrypt(&mut self, data: &mut [u8]) -> Result<(), IceError> {
+        check_block_aligned(data)?;
+        let mut v = u64::from_be_bytes(self.iv);
+        for block in data.chunks_exact_mut(BLOCK_SIZE) {
+            let p = u64::from_be_bytes(block.try_into().unwrap());
+            let c = self.ice.encrypt_u64(p ^ v);
+            v = p ^ c;
+            block.copy_from_slice(&c.to_be_bytes());
+        }
+        self.iv = v.to_be_bytes();
+        Ok(())
+    }
+
+    /// Decrypts the provided data in-place.
+    ///
+    /// # Errors
+    /// Returns [`IceError::NotBlockAligned`] if `data.len()` is not a multiple of 8.
+    pub fn decrypt(&mut self, data: &mut [u8]) -> Result<(), IceError> {
+        check_block_aligned(data)?;
+        let mut v = u64::from_be_bytes(self.iv);
+        for block in data.chunks_exact_mut(BLOCK_SIZE) {
+            let c = u64::from_be_bytes(block.try_into().unwrap());
+            let p = self.ice.decrypt_u64(c) ^ v;
+            v = p ^ c;
+            block.copy_from_slice(&p.to_be_bytes());
+        }
+        self.iv = v.to_be_bytes();
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+//! Block padding for inputs that are not a multiple of the 8-byte block size.
+//!
+//! Unpadding inspects the whole last block with branch-free masks and only reports whether the
+//! padding was valid, not which byte was wrong.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::icefast::{BLOCK_SIZE, IceKernel};
+use crate::{Ice, PaddingError};
+
+/// Padding scheme applied to the final block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Padding {
+    /// `n` bytes of value `n` (1 to 8).
+    Pkcs7,
+    /// `n - 1` zero bytes followed by `n`.
+    AnsiX923,
+    /// `n - 1` random bytes followed by `n`.
+    ///
+    /// With the `std` feature the bytes come from the operating system's random number generator.
+    /// Without it they are **not random**: they are the encryption of the message length, so
+    /// equal-length messages under one key get the same filler.
+    Iso10126,
+    /// A `0x80` byte followed by `n - 1` zero bytes.
+    Iso7816,
+    /// Zero bytes up to the next block boundary, none if already aligned.
+    ///
+    /// Unpadding strips every trailing zero of the last block, so this is only reversible for
+    /// data that does not end in `0x00`.
+    Zero,
+}
+
+impl Padding {
+    /// Appends the padding for `data` so that its length becomes a multiple of 8.
+    fn pad(self, ice: &Ice, data: &mut Vec<u8>) {
+        let n = BLOCK_SIZE - data.len() % BLOCK_SIZE;
+        match self {
+            Padding::Pkcs7 => data.resize(data.len() + n, n as u8),
+            Padding::AnsiX923 => {
+                data.resize(data.len() + n - 1, 0);
+                data.push(n as u8);
+            }
+            Padding::Iso10126 => {
+                let random = random_block(ice, data.len());
+                data.extend_from_slice(&random[..n - 1]);
+                data.push(n as u8);
+            }
+            Padding::Iso7816 => {
+                data.push(0x80);
+                data.resize(data.len() + n - 1, 0);
+            }
+            Padding::Zero => data.resize(data.len().next_multiple_of(BLOCK_SIZE), 0),
+        }
+    }
+
+    /// Returns the unpadded length of the block-aligned `data`.
+    fn unpadded_len(self, data: &[u8]) -> Result<usize, PaddingError> {
+        if !data.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(PaddingError::NotBlockAligned(data.len()));
+        }
+        let Some(last) = data.last_chunk::<BLOCK_SIZE>() else {
+            return match self {
+                Padding::Zero => Ok(0),
+                _ => Err(PaddingError::Invalid),
+            };
+        };
+
+        let (bad, pad_len) = match self {
+            Padding::Pkcs7 | Padding::AnsiX923 | Padding::Iso10126 => {
+                let n = last[BLOCK_SIZE - 1] as u32;
+                let mut bad = ct_is_zero(n) | !ct_lt(n, BLOCK_SIZE as u32 + 1);
+                for (i, &b) in last[..BLOCK_SIZE - 1].iter().enumerate() {
+                    let in_pad = !ct_lt(i as u32, (BLOCK_SIZE as u32).wrapping_sub(n));
+                    bad |= match self {
+                        Padding::Pkcs7 => in_pad & !ct_is_zero(b as u32 ^ n),
+                        Padding::AnsiX923 => in_pad & !ct_is_zero(b as u32),
+                        _ => 0,
+                    };
+                }
+                (bad, n)
+            }
+            Padding::Iso7816 => {
+                let (mut bad, mut searching, mut pad_len) = (0, u32::MAX, 0);
+                for (i, &b) in last.iter().rev().enumerate() {
+                    let marker = ct_is_zero(b as u32 ^ 0x80);
+                    bad |= searching & !marker & !ct_is_zero(b as u32);
+                    pad_len |= searching & marker & (i as u32 + 1);
+                    searching &= !marker;
+                }
+                (bad | searching, pad_len)
+            }
+            Padding::Zero => {
+                let (mut searching, mut pad_len) = (u32::MAX, 0u32);
+                for &b in last.iter().rev() {
+                    searching &= ct_is_zero(b as u32);
+                    pad_len += searching & 1;
+                }
+                (0, pad_len)
+            }
+        };
+
+        if bad != 0 {
+            return Err(PaddingError::Invalid);
+        }
+        Ok(data.len() - pad_len as usize)
+    }
+}
+
+/// Padded entry points.
+///
+/// The padded data goes through the same serial/parallel dispatch as `encrypt_auto`.
+impl Ice {
+    /// Pads `data` in-place and encrypts it.
+    ///
+    /// # Panics
+    /// With [`Padding::Iso10126`], panics if the system random number generator fails.
+    pub fn encrypt_padded(&self, data: &mut Vec<u8>, padding: Padding) {
+        padding.pad(self, data);
+        if !data.is_empty() {
+            self.dispatch_auto::<false>(data);
+        }
+    }
+
+    /// Decrypts `data` in-place and returns the plaintext without its padding.
+    ///
+    /// # Errors
+    /// Returns [`PaddingError::NotBlockAligned`] without touching `data` if its length is not a
+    /// multiple of 8, or [`PaddingError::Invalid`] if the decrypted padding is malformed. In the
+    /// latter case `data` holds the decrypted bytes.
+    pub fn decrypt_padded<'a>(
+        &self,
+        data: &'a mut [u8],
+        padding: Padding,
+    ) -> Result<&'a [u8], PaddingError> {
+        if !data.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(PaddingError::NotBlockAligned(data.len()));
+        }
+        if !data.is_empty() {
+            self.dispatch_auto::<true>(data);
+        }
+        let len = padding.unpadded_len(data)?;
+        Ok(&data[..len])
+    }
+
+    /// Returns the padded encryption of `data`.
+    ///
+    /// # Panics
+    /// As [`encrypt_padded`](Self::encrypt_padded).
+    pub fn encrypt_padded_vec(&self, data: &[u8], padding: Padding) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len() + BLOCK_SIZE);
+        out.extend_from_slice(data);
+        self.encrypt_padded(&mut out, padding);
+        out
+    }
+
+    /// Returns the decryption of `data` without its padding.
+    ///
+    /// # Errors
+    /// Returns [`PaddingError::NotBlockAligned`] or [`PaddingError::Invalid`] as
+    /// [`decrypt_padded`](Self::decrypt_padded) does.
+    pub fn decrypt_padded_vec(
+        &self,
+        data: &[u8],
+        padding: Padding,
+    ) -> Result<Vec<u8>, PaddingError> {
+        if !data.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(PaddingError::NotBlockAligned(data.len()));
+        }
+        let mut out = vec![0u8; data.len()];
+        if !data.is_empty() {
+            self.dispatch_auto_to::<true>(data, &mut out);
+        }
+        let len = padding.unpadded_len(&out)?;
+        out.truncate(len);
+        Ok(out)
+    }
+}
+
+/// Filler bytes for ISO 10126 padding, from the operating system.
+#[cfg(feature = "std")]
+fn random_block(_ice: &Ice, _len: usize) -> [u8; 8] {
+    let mut block = [0u8; 8];
+    getrandom::fill(&mut block).expect("the system random number generator failed");
+    block
+}
+
+/// Filler bytes for ISO 10126 padding. Without `std` there is no entropy source, so the bytes
+/// are derived from the key and length: unpredictable without the key, but not random.
+#[cfg(not(feature = "std"))]
+fn random_block(ice: &Ice, len: usize) -> [u8; 8] {
+    ice.encrypt_u64(len as u64).to_le_bytes()
+}
+
+/// Returns all ones if `x` is zero, otherwise zero.
+#[inline(always)]
+fn ct_is_zero(x: u32) -> u32 {
+    ((x | x.wrapping_neg()) >> 31).wrapping_sub(1)
+}
+
+/// Returns all ones if `a < b`, otherwise zero. Both must be below 2^31.
+#[inline(always)]
+fn ct_lt(a: u32, b: u32) -> u32 {
+    (a.wrapping_sub(b) >> 31).wrapping_neg()
+}
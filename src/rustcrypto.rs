@@ -0,0 +1,170 @@
+//! [RustCrypto `cipher`](https://docs.rs/cipher) trait implementations, behind the
+//! `rustcrypto` feature.
+//!
+//! Each fixed level, [`IceThin`] and [`Ice1`] to [`Ice8`], implements `KeyInit`,
+//! `BlockCipherEncrypt` and `BlockCipherDecrypt`, so it can be used with generic mode crates such
+//! as `cbc` and `ctr`. [`Ice`] implements the block traits too, but not `KeyInit`, as its key
+//! size depends on the level. The backends hand batches of blocks to the vectorized kernel
+//! rather than processing one block at a time.
+
+use cipher::consts::{U8, U16, U24, U32, U40, U48, U56, U64};
+use cipher::{
+    Block, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncBackend,
+    BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser, InOut, InOutBuf, Key, KeyInit,
+    KeySizeUser, ParBlocks, ParBlocksSizeUser,
+};
+
+use crate::icefast::{BLOCK_SIZE, IceKernel};
+use crate::{Ice, Ice1, Ice2, Ice3, Ice4, Ice5, Ice6, Ice7, Ice8, IceFixed, IceThin};
+
+/// Blocks per backend batch; `ParBlocksSize` must match.
+const PAR_BLOCKS: usize = 16;
+
+macro_rules! impl_rustcrypto {
+    ($ty:ident, $key_size:ty) => {
+        impl KeySizeUser for $ty {
+            type KeySize = $key_size;
+        }
+
+        impl KeyInit for $ty {
+            fn new(key: &Key<Self>) -> Self {
+                <$ty>::new(&key.0)
+            }
+        }
+    };
+}
+
+impl_rustcrypto!(IceThin, U8);
+impl_rustcrypto!(Ice1, U8);
+impl_rustcrypto!(Ice2, U16);
+impl_rustcrypto!(Ice3, U24);
+impl_rustcrypto!(Ice4, U32);
+impl_rustcrypto!(Ice5, U40);
+impl_rustcrypto!(Ice6, U48);
+impl_rustcrypto!(Ice7, U56);
+impl_rustcrypto!(Ice8, U64);
+
+impl<const ROUNDS: usize> BlockSizeUser for IceFixed<ROUNDS> {
+    type BlockSize = U8;
+}
+
+impl<const ROUNDS: usize> BlockCipherEncrypt for IceFixed<ROUNDS> {
+    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = U8>) {
+        f.call(&Backend(self));
+    }
+}
+
+impl<const ROUNDS: usize> BlockCipherDecrypt for IceFixed<ROUNDS> {
+    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = U8>) {
+        f.call(&Backend(self));
+    }
+}
+
+impl BlockSizeUser for Ice {
+    type BlockSize = U8;
+}
+
+impl BlockCipherEncrypt for Ice {
+    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = U8>) {
+        f.call(&Backend(self));
+    }
+}
+
+impl BlockCipherDecrypt for Ice {
+    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = U8>) {
+        f.call(&Backend(self));
+    }
+}
+
+/// Encryption and decryption backend over an ICE kernel.
+struct Backend<'a, K>(&'a K);
+
+impl<K> BlockSizeUser for Backend<'_, K> {
+    type BlockSize = U8;
+}
+
+impl<K> ParBlocksSizeUser for Backend<'_, K> {
+    type ParBlocksSize = U16;
+}
+
+impl<K: IceKernel> Backend<'_, K> {
+    #[inline(always)]
+    fn process_block<const DECRYPT: bool>(&self, mut block: InOut<'_, '_, Block<Self>>) {
+        let mut buf: [u8; BLOCK_SIZE] = block.get_in().0;
+        self.0
+            .process_chunk::<1, DECRYPT>(self.0.kernel::<1>(), &mut buf);
+        block.get_out().0 = buf;
+    }
+
+    #[inline(always)]
+    fn process_par_blocks<const DECRYPT: bool>(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let mut buf = [0u8; PAR_BLOCKS * BLOCK_SIZE];
+        for (chunk, block) in buf.chunks_exact_mut(BLOCK_SIZE).zip(blocks.get_in().iter()) {
+            chunk.copy_from_slice(block);
+        }
+        self.0
+            .process_chunk::<PAR_BLOCKS, DECRYPT>(self.0.kernel::<PAR_BLOCKS>(), &mut buf);
+        for (block, chunk) in blocks
+            .get_out()
+            .iter_mut()
+            .zip(buf.chunks_exact(BLOCK_SIZE))
+        {
+            block.copy_from_slice(chunk);
+        }
+    }
+
+    #[inline(always)]
+    fn process_tail_blocks<const DECRYPT: bool>(&self, mut blocks: InOutBuf<'_, '_, Block<Self>>) {
+        let len = blocks.len();
+        if len == 0 {
+            return;
+        }
+        let mut buf = [0u8; PAR_BLOCKS * BLOCK_SIZE];
+        let buf = &mut buf[..len * BLOCK_SIZE];
+        for (chunk, block) in buf.chunks_exact_mut(BLOCK_SIZE).zip(blocks.get_in()) {
+            chunk.copy_from_slice(block);
+        }
+        self.0.dispatch_serial::<DECRYPT>(buf);
+        for (block, chunk) in blocks
+            .get_out()
+            .iter_mut()
+            .zip(buf.chunks_exact(BLOCK_SIZE))
+        {
+            block.copy_from_slice(chunk);
+        }
+    }
+}
+
+impl<K: IceKernel> BlockCipherEncBackend for Backend<'_, K> {
+    #[inline(always)]
+    fn encrypt_block(&self, block: InOut<'_, '_, Block<Self>>) {
+        self.process_block::<false>(block);
+    }
+
+    #[inline(always)]
+    fn encrypt_par_blocks(&self, blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        self.process_par_blocks::<false>(blocks);
+    }
+
+    #[inline(always)]
+    fn encrypt_tail_blocks(&self, blocks: InOutBuf<'_, '_, Block<Self>>) {
+        self.process_tail_blocks::<false>(blocks);
+    }
+}
+
+impl<K: IceKernel> BlockCipherDecBackend for Backend<'_, K> {
+    #[inline(always)]
+    fn decrypt_block(&self, block: InOut<'_, '_, Block<Self>>) {
+        self.process_block::<true>(block);
+    }
+
+    #[inline(always)]
+    fn decrypt_par_blocks(&self, blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        self.process_par_blocks::<true>(blocks);
+    }
+
+    #[inline(always)]
+    fn decrypt_tail_blocks(&self, blocks: InOutBuf<'_, '_, Block<Self>>) {
+        self.process_tail_blocks::<true>(blocks);
+    }
+}
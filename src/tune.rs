@@ -0,0 +1,296 @@
+//! Per-host calibration of the dispatch tuning in [`IceConfig`].
+//!
+//! [`calibrate`] times the batch kernels and the serial/parallel crossover for each level on the
+//! current machine, since both depend heavily on the CPU and a level 2 block costs four times a
+//! Thin-ICE one. The resulting [`TuneProfile`] round-trips through text with `Display` and
+//! `FromStr`, so it can be measured once per host and loaded at startup.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use icefast::Ice;
+//! use icefast::tune::{self, TuneProfile};
+//!
+//! std::fs::write("icefast.profile", tune::calibrate().to_string()).unwrap();
+//!
+//! let profile: TuneProfile = std::fs::read_to_string("icefast.profile")
+//!     .unwrap()
+//!     .parse()
+//!     .unwrap();
+//! let mut ice = Ice::new(2, &[0x3C; 16]);
+//! ice.load_profile(&profile);
+//! ```
+
+use core::fmt;
+use core::hint::black_box;
+use core::ops::RangeInclusive;
+use core::str::FromStr;
+use std::time::{Duration, Instant};
+use std::vec;
+
+use crate::config::DEFAULT_PARALLEL_THRESHOLD;
+use crate::{Ice, IceConfig, IceLevel, ProfileError};
+
+const LEVELS: usize = IceLevel::MAX + 1;
+
+/// Key used for timing; the kernels do not depend on its value.
+const KEY: [u8; 64] = [0x5A; 64];
+
+/// Buffer length for timing the serial batch sizes; a multiple of the largest batch.
+const SERIAL_BYTES: usize = 32_768;
+
+/// Buffer length for timing the parallel batch sizes.
+#[cfg(feature = "parallel")]
+const PARALLEL_BYTES: usize = 262_144;
+
+/// Buffer lengths tried for the serial/parallel crossover, as powers of two (4 KB to 1 MB).
+#[cfg(feature = "parallel")]
+const CROSSOVER_EXPS: RangeInclusive<u32> = 12..=20;
+
+/// Timed runs per measurement, after one warm-up run; the median is kept, so a single
+/// descheduled or unusually lucky run does not decide the setting.
+const RUNS: usize = 9;
+
+/// The calibrated settings for one ICE level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LevelTuning {
+    parallel_threshold: usize,
+    max_serial_batch_exp: u32,
+    max_parallel_batch_exp: u32,
+}
+
+impl LevelTuning {
+    const DEFAULT: Self = Self {
+        parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+        max_serial_batch_exp: IceConfig::MAX_SERIAL_BATCH_EXP,
+        max_parallel_batch_exp: IceConfig::MAX_PARALLEL_BATCH_EXP,
+    };
+
+    /// Input length in bytes from which the `_auto` methods process in parallel.
+    pub fn parallel_threshold(&self) -> usize {
+        self.parallel_threshold
+    }
+
+    /// Largest serial batch, as a power of two of 8-byte blocks.
+    pub fn max_serial_batch_exp(&self) -> u32 {
+        self.max_serial_batch_exp
+    }
+
+    /// Largest parallel batch, as a power of two of 8-byte blocks.
+    pub fn max_parallel_batch_exp(&self) -> u32 {
+        self.max_parallel_batch_exp
+    }
+}
+
+/// Calibrated dispatch tuning for every ICE level.
+///
+/// The text form has one line per level, and `#` starts a comment line:
+///
+/// ```text
+/// level 2: parallel_threshold=16384 max_serial_batch_exp=5 max_parallel_batch_exp=6
+/// ```
+///
+/// Levels and keys missing from the text keep the built-in defaults.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TuneProfile {
+    levels: [LevelTuning; LEVELS],
+}
+
+impl TuneProfile {
+    /// The settings for `level`.
+    pub fn level(&self, level: IceLevel) -> LevelTuning {
+        self.levels[level.level()]
+    }
+}
+
+impl Default for TuneProfile {
+    /// The built-in defaults for every level, ignoring the environment.
+    fn default() -> Self {
+        Self {
+            levels: [LevelTuning::DEFAULT; LEVELS],
+        }
+    }
+}
+
+impl fmt::Display for TuneProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# icefast tuning profile")?;
+        for (level, tuning) in self.levels.iter().enumerate() {
+            writeln!(
+                f,
+                "level {level}: parallel_threshold={} max_serial_batch_exp={} max_parallel_batch_exp={}",
+                tuning.parallel_threshold,
+                tuning.max_serial_batch_exp,
+                tuning.max_parallel_batch_exp
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TuneProfile {
+    type Err = ProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut profile = TuneProfile::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let malformed = ProfileError::Malformed { line: i + 1 };
+            let out_of_range = ProfileError::OutOfRange { line: i + 1 };
+
+            let (head, fields) = line.split_once(':').ok_or(malformed)?;
+            let level: usize = head
+                .trim()
+                .strip_prefix("level")
+                .and_then(|n| n.trim().parse().ok())
+                .ok_or(malformed)?;
+            let tuning = profile.levels.get_mut(level).ok_or(out_of_range)?;
+
+            for field in fields.split_whitespace() {
+                let (key, value) = field.split_once('=').ok_or(malformed)?;
+                let exp = |max| match value.parse() {
+                    Ok(exp) if exp <= max => Ok(exp),
+                    Ok(_) => Err(out_of_range),
+                    Err(_) => Err(malformed),
+                };
+                match key {
+                    "parallel_threshold" => {
+                        tuning.parallel_threshold = value.parse().map_err(|_| malformed)?;
+                    }
+                    "max_serial_batch_exp" => {
+                        tuning.max_serial_batch_exp = exp(IceConfig::MAX_SERIAL_BATCH_EXP)?;
+                    }
+                    "max_parallel_batch_exp" => {
+                        tuning.max_parallel_batch_exp = exp(IceConfig::MAX_PARALLEL_BATCH_EXP)?;
+                    }
+                    _ => return Err(malformed),
+                }
+            }
+        }
+        Ok(profile)
+    }
+}
+
+impl Ice {
+    /// Replace the dispatch tuning with the profile's settings for this instance's level.
+    ///
+    /// The thread pool, if any, is kept.
+    pub fn load_profile(&mut self, profile: &TuneProfile) {
+        let tuning = profile.level(self.level());
+        let config = self
+            .config
+            .get_or_insert_with(|| IceConfig::global().clone());
+        config.parallel_threshold = tuning.parallel_threshold;
+        config.max_serial_batch_exp = tuning.max_serial_batch_exp;
+        config.max_parallel_batch_exp = tuning.max_parallel_batch_exp;
+    }
+}
+
+/// Measure every level on the current host.
+///
+/// This takes a few seconds in an optimized build. Parallel settings keep their defaults
+/// without the `parallel` feature or when rayon's global pool has a single thread.
+pub fn calibrate() -> TuneProfile {
+    let levels: [IceLevel; LEVELS] = core::array::from_fn(|n| IceLevel::new(n).unwrap());
+    calibrate_levels(&levels)
+}
+
+/// Measure only `levels` on the current host; the others keep the built-in defaults.
+pub fn calibrate_levels(levels: &[IceLevel]) -> TuneProfile {
+    let mut profile = TuneProfile::default();
+    for &level in levels {
+        profile.levels[level.level()] = calibrate_level(level);
+    }
+    profile
+}
+
+fn calibrate_level(level: IceLevel) -> LevelTuning {
+    let mut tuning = LevelTuning::DEFAULT;
+
+    // With a buffer that is a multiple of the largest batch, the exponent picks `B` exactly.
+    let mut data = vec![0u8; SERIAL_BYTES];
+    tuning.max_serial_batch_exp = fastest(0..=IceConfig::MAX_SERIAL_BATCH_EXP, |exp| {
+        let ice = tuned(level, exp, tuning.max_parallel_batch_exp);
+        time(|| serial_pass(&ice, &mut data))
+    });
+
+    #[cfg(feature = "parallel")]
+    if rayon::current_num_threads() >= 2 {
+        let mut data = vec![0u8; PARALLEL_BYTES];
+        tuning.max_parallel_batch_exp = fastest(0..=IceConfig::MAX_PARALLEL_BATCH_EXP, |exp| {
+            let ice = tuned(level, tuning.max_serial_batch_exp, exp);
+            time(|| parallel_pass(&ice, &mut data))
+        });
+
+        let ice = tuned(
+            level,
+            tuning.max_serial_batch_exp,
+            tuning.max_parallel_batch_exp,
+        );
+        tuning.parallel_threshold = crossover(&ice);
+    }
+
+    tuning
+}
+
+/// The smallest tried length from which parallel processing wins at every larger length.
+///
+/// If it never wins, this is the largest tried length: nothing above it was measured, so the
+/// threshold is not pushed out to where the `_auto` methods could never go parallel.
+#[cfg(feature = "parallel")]
+fn crossover(ice: &Ice) -> usize {
+    let mut data = vec![0u8; 1 << CROSSOVER_EXPS.end()];
+    let mut threshold = 1 << CROSSOVER_EXPS.end();
+    for exp in CROSSOVER_EXPS.rev() {
+        let chunk = &mut data[..1 << exp];
+        let serial = time(|| serial_pass(ice, chunk));
+        let parallel = time(|| parallel_pass(ice, chunk));
+        if parallel >= serial {
+            break;
+        }
+        threshold = 1 << exp;
+    }
+    threshold
+}
+
+fn tuned(level: IceLevel, serial_exp: u32, parallel_exp: u32) -> Ice {
+    Ice::builder()
+        .level(level.level())
+        .key(&KEY)
+        .max_serial_batch_exp(serial_exp)
+        .max_parallel_batch_exp(parallel_exp)
+        .build()
+        .expect("calibration settings are in range")
+}
+
+fn fastest(exps: RangeInclusive<u32>, mut measure: impl FnMut(u32) -> Duration) -> u32 {
+    exps.min_by_key(|&exp| measure(exp)).unwrap()
+}
+
+/// Encrypts and decrypts `data` serially; the two directions share the settings, so both count.
+fn serial_pass(ice: &Ice, data: &mut [u8]) {
+    ice.encrypt(black_box(&mut *data));
+    ice.decrypt(black_box(data));
+}
+
+/// Encrypts and decrypts `data` in parallel.
+#[cfg(feature = "parallel")]
+fn parallel_pass(ice: &Ice, data: &mut [u8]) {
+    ice.encrypt_par(black_box(&mut *data));
+    ice.decrypt_par(black_box(data));
+}
+
+/// The median of [`RUNS`] timed runs after a warm-up run.
+fn time(mut run: impl FnMut()) -> Duration {
+    run();
+    let mut times: [Duration; RUNS] = core::array::from_fn(|_| {
+        let start = Instant::now();
+        run();
+        start.elapsed()
+    });
+    times.sort_unstable();
+    times[RUNS / 2]
+}
@@ -0,0 +1,185 @@
+use icefast::{
+    Backend, Ice, Ice1, Ice2, Ice3, Ice4, Ice5, Ice6, Ice7, Ice8, IceError, IceFixed, IceLevel,
+    IceThin,
+};
+
+mod common;
+use common::{CIPHER_TEXT_16_LEVEL1, EXPECT_TEXT_16, KEY8};
+
+fn with_backend(level: usize, key: &[u8], backend: Backend) -> Ice {
+    Ice::builder()
+        .level(level)
+        .key(key)
+        .backend(backend)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn selected_backend_is_available() {
+    let selected = Backend::selected();
+    assert!(selected.is_available());
+    assert!(Backend::Portable.is_available());
+    // Nothing better than the selection is available.
+    assert!(
+        Backend::ALL
+            .iter()
+            .skip_while(|&&b| b != selected)
+            .skip(1)
+            .all(|b| !b.is_available())
+    );
+    assert_eq!(Ice::new(0, &KEY8).config().backend(), selected);
+}
+
+#[test]
+fn unavailable_backend_is_rejected() {
+    for backend in Backend::ALL {
+        let result = Ice::builder().key(&KEY8).backend(backend).build();
+        if backend.is_available() {
+            assert_eq!(result.unwrap().config().backend(), backend);
+        } else {
+            assert_eq!(result.unwrap_err(), IceError::UnsupportedBackend(backend));
+        }
+    }
+}
+
+#[test]
+fn each_backend_matches_portable() {
+    // Odd lengths exercise every batch size, including batches narrower than a vector.
+    let plain: Vec<u8> = (0..8 * 1_023u32).map(|i| (i * 29 + 7) as u8).collect();
+
+    for level in 0..=IceLevel::MAX {
+        let key: Vec<u8> = (0..64u8)
+            .map(|i| i.wrapping_mul(37) ^ level as u8)
+            .collect();
+        let portable = with_backend(level, &key, Backend::Portable);
+        let mut expected = plain.clone();
+        portable.encrypt(&mut expected);
+
+        // The constant-time backend pads narrow batches to 32 blocks, so it gets its own sweep.
+        let backends = Backend::ALL
+            .into_iter()
+            .filter(|&b| b.is_available() && b != Backend::ConstantTime);
+        for backend in backends {
+            let ice = with_backend(level, &key, backend);
+            for exp in [0, 3, 4, 9] {
+                let ice = Ice::builder()
+                    .config(ice.config().clone())
+                    .level(level)
+                    .key(&key)
+                    .max_serial_batch_exp(exp)
+                    .build()
+                    .unwrap();
+                let mut data = plain.clone();
+                ice.encrypt(&mut data);
+                assert_eq!(data, expected, "{backend} level {level} batch 2^{exp}");
+                ice.decrypt(&mut data);
+                assert_eq!(data, plain, "{backend} level {level} batch 2^{exp}");
+            }
+
+            let mut data = vec![0u8; plain.len()];
+            ice.encrypt_auto_to(&plain, &mut data);
+            assert_eq!(data, expected, "{backend} level {level}");
+
+            let mut data = expected[..128].to_vec();
+            ice.decrypt_chunks::<16>(&mut data);
+            assert_eq!(data, plain[..128], "{backend} level {level}");
+        }
+    }
+}
+
+#[test]
+fn constant_time_matches_portable() {
+    // 256 + 64 + 32 blocks fill each lane width once, and the last 7 pad a partial lane.
+    let plain: Vec<u8> = (0..8 * 359u32).map(|i| (i * 29 + 7) as u8).collect();
+
+    for level in 0..=2 {
+        let key: Vec<u8> = (0..16u8)
+            .map(|i| i.wrapping_mul(37) ^ level as u8)
+            .collect();
+        let mut expected = plain.clone();
+        with_backend(level, &key, Backend::Portable).encrypt(&mut expected);
+
+        for exp in [3, 9] {
+            let ice = Ice::builder()
+                .level(level)
+                .key(&key)
+                .backend(Backend::ConstantTime)
+                .max_serial_batch_exp(exp)
+                .build()
+                .unwrap();
+            let mut data = vec![0u8; plain.len()];
+            ice.encrypt_to(&plain, &mut data);
+            assert_eq!(data, expected, "level {level} batch 2^{exp}");
+            ice.decrypt(&mut data);
+            assert_eq!(data, plain, "level {level} batch 2^{exp}");
+        }
+    }
+}
+
+#[test]
+fn each_backend_matches_reference() {
+    for backend in Backend::ALL.into_iter().filter(|b| b.is_available()) {
+        let ice = with_backend(1, &KEY8, backend);
+        let mut data = EXPECT_TEXT_16.repeat(16).into_bytes();
+        ice.encrypt_chunks::<16>(&mut data);
+        for block in data.chunks(16) {
+            assert_eq!(block, CIPHER_TEXT_16_LEVEL1, "{backend}");
+        }
+    }
+}
+
+#[test]
+fn constant_time_is_opt_in() {
+    assert_ne!(Backend::selected(), Backend::ConstantTime);
+    assert!(Backend::ConstantTime.is_available());
+
+    let portable = with_backend(1, &KEY8, Backend::Portable);
+    let ice = with_backend(1, &KEY8, Backend::ConstantTime);
+    assert_eq!(ice.config().backend(), Backend::ConstantTime);
+
+    // Single blocks go through the bitsliced kernel too.
+    let block = *b"abcdefgh";
+    let cipher = portable.encrypt_block(block);
+    assert_eq!(cipher, CIPHER_TEXT_16_LEVEL1[..8]);
+    assert_eq!(ice.encrypt_block(block), cipher);
+    assert_eq!(ice.decrypt_block(cipher), block);
+    assert_eq!(ice.encrypt_lr(1, 2), portable.encrypt_lr(1, 2));
+}
+
+#[test]
+fn constant_time_is_available_to_fixed_levels() {
+    fn check<const ROUNDS: usize>(new: fn(&[u8]) -> IceFixed<ROUNDS>) {
+        let key: Vec<u8> = (0..64u8).map(|i| i.wrapping_mul(73) ^ 0x5A).collect();
+        let portable = new(&key);
+        let ice: IceFixed<ROUNDS> = Ice::builder()
+            .key(&key)
+            .backend(Backend::ConstantTime)
+            .build_fixed()
+            .unwrap();
+        assert_eq!(ice.config().backend(), Backend::ConstantTime);
+
+        // One full lane and a partial one.
+        let plain: Vec<u8> = (0..8 * 40u32).map(|i| (i * 13 + 1) as u8).collect();
+        let mut expected = plain.clone();
+        portable.encrypt(&mut expected);
+        let mut data = plain.clone();
+        ice.encrypt(&mut data);
+        assert_eq!(data, expected, "{ROUNDS} rounds");
+        ice.decrypt(&mut data);
+        assert_eq!(data, plain, "{ROUNDS} rounds");
+
+        let block = *b"abcdefgh";
+        assert_eq!(ice.encrypt_block(block), portable.encrypt_block(block));
+    }
+
+    check(|key| IceThin::new(key[..8].try_into().unwrap()));
+    check(|key| Ice1::new(key[..8].try_into().unwrap()));
+    check(|key| Ice2::new(key[..16].try_into().unwrap()));
+    check(|key| Ice3::new(key[..24].try_into().unwrap()));
+    check(|key| Ice4::new(key[..32].try_into().unwrap()));
+    check(|key| Ice5::new(key[..40].try_into().unwrap()));
+    check(|key| Ice6::new(key[..48].try_into().unwrap()));
+    check(|key| Ice7::new(key[..56].try_into().unwrap()));
+    check(|key| Ice8::new(key.try_into().unwrap()));
+}
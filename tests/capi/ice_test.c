@@ -0,0 +1,82 @@
+/*
+ * Exercises the ice.h API. Built once against ice-reference/ice.c and once against
+ * libicefast (with ICEFAST defined, adding the buffer extensions); the outputs must match.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "ice.h"
+#ifdef ICEFAST
+#include "icefast.h"
+#endif
+
+#define BUFFER_BLOCKS 8192
+
+int
+main (void)
+{
+	unsigned char	key[64], ptxt[8], ctxt[8], check[8];
+	int		level, i;
+
+	for (i = 0; i < 64; i++)
+	    key[i] = (unsigned char) (i * 37 + 11);
+	memcpy (ptxt, "abcdefgh", 8);
+
+	for (level = 0; level <= 8; level++) {
+	    ICE_KEY	*ik = ice_key_create (level);
+
+	    if (ik == NULL)
+		return (1);
+
+	    ice_key_set (ik, key);
+	    ice_key_encrypt (ik, ptxt, ctxt);
+	    ice_key_decrypt (ik, ctxt, check);
+	    if (memcmp (check, ptxt, 8) != 0)
+		return (2);
+
+	    /* In-place use, as allowed by the reference implementation. */
+	    memcpy (check, ptxt, 8);
+	    ice_key_encrypt (ik, check, check);
+	    if (memcmp (check, ctxt, 8) != 0)
+		return (3);
+
+	    printf ("level %d: key %d block %d:", level,
+		    ice_key_key_size (ik), ice_key_block_size (ik));
+	    for (i = 0; i < 8; i++)
+		printf (" %02x", ctxt[i]);
+	    printf ("\n");
+
+#ifdef ICEFAST
+	    {
+		unsigned char	*buf = malloc (BUFFER_BLOCKS * 8);
+		unsigned char	block[8];
+		size_t		b;
+
+		for (b = 0; b < BUFFER_BLOCKS * 8; b++)
+		    buf[b] = (unsigned char) (b * 7);
+		if (ice_key_encrypt_buffer (ik, buf, BUFFER_BLOCKS * 8) != 0)
+		    return (4);
+		for (b = 0; b < BUFFER_BLOCKS; b++) {
+		    for (i = 0; i < 8; i++)
+			block[i] = (unsigned char) ((b * 8 + i) * 7);
+		    ice_key_encrypt (ik, block, block);
+		    if (memcmp (block, buf + b * 8, 8) != 0)
+			return (5);
+		}
+		if (ice_key_decrypt_buffer (ik, buf, BUFFER_BLOCKS * 8) != 0
+			|| ice_key_encrypt_buffer (ik, buf, 13) != -1)
+		    return (6);
+		for (b = 0; b < BUFFER_BLOCKS * 8; b++)
+		    if (buf[b] != (unsigned char) (b * 7))
+			return (7);
+		free (buf);
+	    }
+#endif
+
+	    ice_key_destroy (ik);
+	}
+
+	return (0);
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "capi")]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const HEADER: &str = "include/icefast.h";
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Builds the staticlib from the `icefast-capi` crate in its own target directory, since the one
+/// next to the test binary may come from a build with other features.
+fn build_staticlib() -> PathBuf {
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("capi");
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "-p", "icefast-capi"])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .current_dir(manifest_dir())
+        .status()
+        .unwrap();
+    assert!(status.success(), "building the staticlib failed");
+    target_dir.join("debug/libicefast.a")
+}
+
+fn generate_header() -> String {
+    let config = cbindgen::Config::from_root_or_default(manifest_dir());
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_src(manifest_dir().join("src/capi.rs"))
+        .with_config(config)
+        .generate()
+        .expect("cbindgen should parse src/capi.rs")
+        .write(&mut header);
+    String::from_utf8(header).unwrap()
+}
+
+/// Compiles `tests/capi/ice_test.c` with the extra `args`, then returns its output.
+fn run_c_test(name: &str, args: &[PathBuf]) -> String {
+    let out = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".into()))
+        .arg("-I")
+        .arg(manifest_dir().join("ice-reference"))
+        .arg(manifest_dir().join("tests/capi/ice_test.c"))
+        .args(args)
+        .arg("-o")
+        .arg(&out)
+        .status()
+        .expect("a C compiler should be available");
+    assert!(status.success(), "compiling {name} failed");
+
+    let output = Command::new(&out).output().unwrap();
+    assert!(output.status.success(), "{name} failed");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let generated = generate_header();
+    let path = manifest_dir().join(HEADER);
+    if std::env::var_os("ICEFAST_UPDATE_HEADER").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    let committed = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "{HEADER} is stale; rerun with ICEFAST_UPDATE_HEADER=1"
+    );
+}
+
+#[test]
+fn c_program_matches_reference() {
+    let reference = run_c_test(
+        "ice_test_reference",
+        &[manifest_dir().join("ice-reference/ice.c")],
+    );
+    let icefast = run_c_test(
+        "ice_test_icefast",
+        &[
+            "-DICEFAST".into(),
+            "-I".into(),
+            manifest_dir().join("include"),
+            build_staticlib(),
+            "-lpthread".into(),
+            "-ldl".into(),
+            "-lm".into(),
+        ],
+    );
+    assert_eq!(icefast, reference);
+    assert!(icefast.contains("level 8"));
+}
+
+#[test]
+fn create_rejects_levels_above_8() {
+    assert!(icefast::capi::ice_key_create(9).is_null());
+    let ik = icefast::capi::ice_key_create(8);
+    assert!(!ik.is_null());
+    // SAFETY: `ik` was just created and is not used again.
+    unsafe { icefast::capi::ice_key_destroy(ik) };
+}
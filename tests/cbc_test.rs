@@ -0,0 +1,111 @@
+// CBC known answers were generated with ice-reference/ice.c's `ice_key_encrypt`.
+use icefast::modes::Cbc;
+use icefast::{Ice, IceError};
+
+mod common;
+use common::{IV, KEY8, large_plaintext};
+
+static PLAIN_TEXT_24: &str = "abcdefghijklmnopqrstuvwx";
+static CBC_CIPHER_TEXT_24_LEVEL0: [u8; 24] = [
+    90, 34, 128, 4, 92, 137, 221, 219, 61, 203, 199, 9, 242, 150, 126, 40, 97, 146, 133, 120, 141,
+    228, 193, 32,
+];
+
+#[test]
+fn cbc_encrypt_level0() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = PLAIN_TEXT_24.as_bytes().to_owned();
+    let mut cbc = Cbc::new(&ice, IV);
+    cbc.encrypt(&mut data).unwrap();
+    assert_eq!(data, CBC_CIPHER_TEXT_24_LEVEL0);
+    assert_eq!(cbc.iv(), CBC_CIPHER_TEXT_24_LEVEL0[16..]);
+}
+
+#[test]
+fn cbc_decrypt_level0() {
+    let ice = Ice::new(0, &KEY8);
+
+    let mut data = CBC_CIPHER_TEXT_24_LEVEL0.to_owned();
+    Cbc::new(&ice, IV).decrypt(&mut data).unwrap();
+    assert_eq!(data, PLAIN_TEXT_24.as_bytes());
+
+    #[cfg(feature = "parallel")]
+    {
+        let mut data = CBC_CIPHER_TEXT_24_LEVEL0.to_owned();
+        Cbc::new(&ice, IV).decrypt_par(&mut data).unwrap();
+        assert_eq!(data, PLAIN_TEXT_24.as_bytes());
+    }
+
+    let mut dst = [0u8; 24];
+    Cbc::new(&ice, IV)
+        .decrypt_to(&CBC_CIPHER_TEXT_24_LEVEL0, &mut dst)
+        .unwrap();
+    assert_eq!(dst, PLAIN_TEXT_24.as_bytes());
+}
+
+#[test]
+fn cbc_streaming_matches_one_shot() {
+    let ice = Ice::new(1, &KEY8);
+    let plain = large_plaintext();
+
+    let mut one_shot = plain.clone();
+    Cbc::new(&ice, IV).encrypt(&mut one_shot).unwrap();
+
+    let mut streamed = plain.clone();
+    let mut cbc = Cbc::new(&ice, IV);
+    for piece in streamed.chunks_mut(4_000) {
+        cbc.encrypt(piece).unwrap();
+    }
+    assert_eq!(streamed, one_shot);
+
+    let mut cbc = Cbc::new(&ice, IV);
+    let (head, tail) = streamed.split_at_mut(40_000);
+    #[cfg(feature = "parallel")]
+    cbc.decrypt_par(head).unwrap();
+    #[cfg(not(feature = "parallel"))]
+    cbc.decrypt(head).unwrap();
+    cbc.decrypt(tail).unwrap();
+    assert_eq!(streamed, plain);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn cbc_parallel_decrypt_matches_serial() {
+    let ice = Ice::new(2, &[0x3Cu8; 16]);
+    let plain = large_plaintext();
+    let mut ciphertext = plain.clone();
+    Cbc::new(&ice, IV).encrypt(&mut ciphertext).unwrap();
+
+    let mut data = ciphertext.clone();
+    Cbc::new(&ice, IV).decrypt_auto(&mut data).unwrap();
+    assert_eq!(data, plain);
+
+    let mut dst = vec![0u8; ciphertext.len()];
+    let mut cbc = Cbc::new(&ice, IV);
+    cbc.decrypt_par_to(&ciphertext, &mut dst).unwrap();
+    assert_eq!(dst, plain);
+    assert_eq!(cbc.iv(), ciphertext[ciphertext.len() - 8..]);
+}
+
+#[test]
+fn cbc_rejects_bad_lengths() {
+    let ice = Ice::new(0, &KEY8);
+    let mut cbc = Cbc::new(&ice, IV);
+    let mut data = [0u8; 12];
+    assert_eq!(cbc.encrypt(&mut data), Err(IceError::NotBlockAligned(12)));
+    #[cfg(feature = "parallel")]
+    assert_eq!(
+        cbc.decrypt_par(&mut data),
+        Err(IceError::NotBlockAligned(12))
+    );
+
+    let mut dst = [0u8; 8];
+    assert_eq!(
+        cbc.decrypt_to(&[0u8; 16], &mut dst),
+        Err(IceError::LengthMismatch {
+            expected: 16,
+            actual: 8
+        })
+    );
+    assert_eq!(cbc.iv(), IV);
+}
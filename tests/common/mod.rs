@@ -0,0 +1,21 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+pub static KEY8: [u8; 8] = [0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00];
+
+pub static IV: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+pub static EXPECT_TEXT_16: &str = "abcdefghijklmnop";
+
+pub static CIPHER_TEXT_16_LEVEL0: [u8; 16] = [
+    195, 233, 103, 103, 181, 234, 50, 163, 218, 3, 22, 226, 147, 169, 252, 216,
+];
+
+pub static CIPHER_TEXT_16_LEVEL1: [u8; 16] = [
+    49, 188, 85, 204, 107, 67, 206, 70, 250, 115, 122, 182, 89, 128, 168, 130,
+];
+
+/// About 100 KB of block-aligned data, enough to take the parallel paths.
+pub fn large_plaintext() -> Vec<u8> {
+    (0..100_008u32).map(|i| (i * 7) as u8).collect()
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "std")]
+// Kept in its own test binary: the environment is only read once per process.
+use icefast::{Ice, IceConfig, IceThin};
+
+#[test]
+fn environment_overrides_defaults() {
+    // SAFETY: this is the only test in the binary, so nothing else reads the environment.
+    unsafe {
+        std::env::set_var("ICEFAST_PARALLEL_THRESHOLD", " 4096 ");
+        std::env::set_var("ICEFAST_MAX_SERIAL_BATCH_EXP", "4");
+        std::env::set_var("ICEFAST_MAX_PARALLEL_BATCH_EXP", "12");
+    }
+
+    let ice = Ice::new(0, &[0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00]);
+    assert_eq!(ice.config().parallel_threshold(), 4096);
+    assert_eq!(ice.config().max_serial_batch_exp(), 4);
+    // Out of range, so ignored.
+    assert_eq!(
+        ice.config().max_parallel_batch_exp(),
+        IceConfig::MAX_PARALLEL_BATCH_EXP
+    );
+
+    // The built-in defaults are unaffected, and builder settings win.
+    assert_eq!(IceConfig::new().parallel_threshold(), 32_768);
+    let ice = Ice::builder()
+        .key(&[0u8; 8])
+        .parallel_threshold(1)
+        .build()
+        .unwrap();
+    assert_eq!(ice.config().parallel_threshold(), 1);
+    assert_eq!(ice.config().max_serial_batch_exp(), 4);
+
+    let mut data = vec![0u8; 10_000];
+    let mut expected = data.clone();
+    IceThin::new(&[0u8; 8]).encrypt_auto(&mut data);
+    ice.encrypt(&mut expected);
+    assert_eq!(data, expected);
+}
@@ -0,0 +1,208 @@
+use icefast::{Ice, Ice2, IceConfig, IceError, IceThin};
+
+mod common;
+use common::{CIPHER_TEXT_16_LEVEL1, EXPECT_TEXT_16, KEY8, large_plaintext};
+
+#[test]
+fn builder_matches_new() {
+    let ice = Ice::builder().level(1).key(&KEY8).build().unwrap();
+    let mut data = EXPECT_TEXT_16.as_bytes().to_owned();
+    ice.encrypt(&mut data);
+    assert_eq!(data, CIPHER_TEXT_16_LEVEL1);
+
+    let defaults = IceConfig::default();
+    assert_eq!(
+        ice.config().parallel_threshold(),
+        defaults.parallel_threshold()
+    );
+    assert_eq!(
+        ice.config().max_serial_batch_exp(),
+        defaults.max_serial_batch_exp()
+    );
+}
+
+#[test]
+fn builder_rejects_bad_settings() {
+    assert_eq!(
+        Ice::builder().level(2).key(&KEY8).build().unwrap_err(),
+        IceError::KeyTooShort {
+            expected: 16,
+            actual: 8
+        }
+    );
+    assert_eq!(
+        Ice::builder().build().unwrap_err(),
+        IceError::KeyTooShort {
+            expected: 8,
+            actual: 0
+        }
+    );
+    assert_eq!(
+        Ice::builder().level(9).key(&[0; 72]).build().unwrap_err(),
+        IceError::UnsupportedLevel(9)
+    );
+    assert_eq!(
+        Ice::builder()
+            .key(&KEY8)
+            .max_serial_batch_exp(10)
+            .build()
+            .unwrap_err(),
+        IceError::UnsupportedBatchExponent { max: 9, actual: 10 }
+    );
+    assert_eq!(
+        Ice::builder()
+            .key(&KEY8)
+            .max_parallel_batch_exp(9)
+            .build()
+            .unwrap_err(),
+        IceError::UnsupportedBatchExponent { max: 8, actual: 9 }
+    );
+}
+
+#[test]
+fn fixed_builder_keeps_its_tuning() {
+    let key = [0x3Cu8; 16];
+    let plain = large_plaintext();
+    let mut expected = plain.clone();
+    Ice2::new(&key).encrypt(&mut expected);
+
+    let ice: Ice2 = Ice::builder()
+        .key(&key)
+        .max_serial_batch_exp(3)
+        .max_parallel_batch_exp(5)
+        .parallel_threshold(8)
+        .build_fixed()
+        .unwrap();
+    assert_eq!(ice.config().max_serial_batch_exp(), 3);
+    assert_eq!(ice.config().max_parallel_batch_exp(), 5);
+    assert_eq!(ice.config().parallel_threshold(), 8);
+
+    let mut data = plain.clone();
+    ice.encrypt_auto(&mut data);
+    assert_eq!(data, expected);
+    ice.decrypt(&mut data);
+    assert_eq!(data, plain);
+
+    // Plain constructors keep sharing the defaults.
+    let defaults = IceConfig::default();
+    assert_eq!(
+        Ice2::new(&key).config().parallel_threshold(),
+        defaults.parallel_threshold()
+    );
+    assert_eq!(
+        Ice::new(2, &key).config().parallel_threshold(),
+        defaults.parallel_threshold()
+    );
+}
+
+#[test]
+fn fixed_builder_rejects_bad_settings() {
+    assert_eq!(
+        Ice::builder().key(&KEY8).build_fixed::<32>().unwrap_err(),
+        IceError::KeyTooShort {
+            expected: 16,
+            actual: 8
+        }
+    );
+    assert_eq!(
+        Ice::builder()
+            .key(&KEY8)
+            .max_serial_batch_exp(10)
+            .build_fixed::<8>()
+            .unwrap_err(),
+        IceError::UnsupportedBatchExponent { max: 9, actual: 10 }
+    );
+
+    // The level set on the builder does not override the type's.
+    let thin: IceThin = Ice::builder().level(8).key(&KEY8).build_fixed().unwrap();
+    let mut data = EXPECT_TEXT_16.as_bytes().to_owned();
+    thin.encrypt(&mut data);
+    let mut expected = EXPECT_TEXT_16.as_bytes().to_owned();
+    IceThin::new(&KEY8).encrypt(&mut expected);
+    assert_eq!(data, expected);
+}
+
+#[test]
+fn tuning_does_not_change_output() {
+    let reference = Ice::new(2, &[0x3Cu8; 16]);
+    let plain = large_plaintext();
+    let mut expected = plain.clone();
+    reference.encrypt(&mut expected);
+
+    for (serial, parallel, threshold) in [(0, 0, 0), (3, 5, 8), (9, 8, usize::MAX)] {
+        let ice = Ice::builder()
+            .level(2)
+            .key(&[0x3Cu8; 16])
+            .max_serial_batch_exp(serial)
+            .max_parallel_batch_exp(parallel)
+            .parallel_threshold(threshold)
+            .build()
+            .unwrap();
+        assert_eq!(ice.config().max_serial_batch_exp(), serial);
+        assert_eq!(ice.config().max_parallel_batch_exp(), parallel);
+        assert_eq!(ice.config().parallel_threshold(), threshold);
+
+        let mut data = plain.clone();
+        ice.encrypt_auto(&mut data);
+        assert_eq!(data, expected, "serial {serial}, parallel {parallel}");
+        ice.decrypt(&mut data);
+        assert_eq!(data, plain, "serial {serial}, parallel {parallel}");
+
+        let mut data = vec![0u8; plain.len()];
+        ice.encrypt_auto_to(&plain, &mut data);
+        assert_eq!(data, expected, "serial {serial}, parallel {parallel}");
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn builder_thread_pool_runs_modes() {
+    use icefast::modes::Cbc;
+    use std::sync::Arc;
+
+    let pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap(),
+    );
+    let ice = Ice::builder()
+        .level(1)
+        .key(&KEY8)
+        .thread_pool(Arc::clone(&pool))
+        .parallel_threshold(8)
+        .build()
+        .unwrap();
+    assert!(Arc::ptr_eq(ice.config().thread_pool().unwrap(), &pool));
+
+    let plain = large_plaintext();
+    let mut expected = plain.clone();
+    Ice::new(1, &KEY8).encrypt(&mut expected);
+
+    let mut data = plain.clone();
+    ice.encrypt_par(&mut data);
+    assert_eq!(data, expected);
+    ice.decrypt_auto(&mut data);
+    assert_eq!(data, plain);
+
+    let iv = [7u8; 8];
+    let mut ciphertext = plain.clone();
+    Cbc::new(&ice, iv).encrypt(&mut ciphertext).unwrap();
+    let mut data = ciphertext.clone();
+    Cbc::new(&ice, iv).decrypt_auto(&mut data).unwrap();
+    assert_eq!(data, plain);
+
+    // A clone shares the pool.
+    let clone = ice.clone();
+    assert!(Arc::ptr_eq(clone.config().thread_pool().unwrap(), &pool));
+
+    let fixed: icefast::Ice1 = Ice::builder()
+        .key(&KEY8)
+        .thread_pool(Arc::clone(&pool))
+        .build_fixed()
+        .unwrap();
+    assert!(Arc::ptr_eq(fixed.config().thread_pool().unwrap(), &pool));
+    let mut data = plain.clone();
+    fixed.encrypt_auto(&mut data);
+    assert_eq!(data, expected);
+}
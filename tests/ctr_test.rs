@@ -0,0 +1,99 @@
+// CTR known answers were generated with ice-reference/ice.c's `ice_key_encrypt`.
+use icefast::modes::Ctr;
+use icefast::{Ice, IceError};
+
+mod common;
+use common::KEY8;
+
+static PLAIN_TEXT_21: &str = "counter mode, any len";
+/// Level 1, nonce 0xC0FFEE in the high 32 bits, counter starting at 0xFFFFFFFE.
+static CTR_CIPHER_TEXT_21_LEVEL1: [u8; 21] = [
+    159, 32, 61, 128, 139, 148, 33, 57, 229, 232, 24, 55, 94, 137, 125, 62, 234, 82, 172, 174, 231,
+];
+
+#[test]
+fn ctr_encrypt_level1() {
+    let ice = Ice::new(1, &KEY8);
+    let mut data = PLAIN_TEXT_21.as_bytes().to_owned();
+    Ctr::with_split(&ice, 0xC0FFEE, 32, 0xFFFF_FFFE)
+        .apply_keystream(&mut data)
+        .unwrap();
+    assert_eq!(data, CTR_CIPHER_TEXT_21_LEVEL1);
+}
+
+#[test]
+fn ctr_streaming_matches_one_shot() {
+    let ice = Ice::new(0, &KEY8);
+    let plain: Vec<u8> = (0..100_003u32).map(|i| (i * 13) as u8).collect();
+
+    let mut one_shot = plain.clone();
+    Ctr::new(&ice, [9; 8])
+        .apply_keystream(&mut one_shot)
+        .unwrap();
+
+    let mut streamed = plain.clone();
+    let mut ctr = Ctr::new(&ice, [9; 8]);
+    for piece in streamed.chunks_mut(4_099) {
+        ctr.apply_keystream(piece).unwrap();
+    }
+    assert_eq!(streamed, one_shot);
+    assert_eq!(ctr.position(), plain.len() as u128);
+}
+
+#[test]
+fn ctr_seek_gives_random_access() {
+    let ice = Ice::new(2, &[0x42u8; 16]);
+    let mut whole = vec![0u8; 50_000];
+    Ctr::new(&ice, [0; 8]).apply_keystream(&mut whole).unwrap();
+
+    let mut ctr = Ctr::new(&ice, [0; 8]);
+    ctr.seek(1_000);
+    let mut window = vec![0u8; 20_005];
+    ctr.apply_keystream(&mut window).unwrap();
+    assert_eq!(window, whole[8_000..28_005]);
+}
+
+#[test]
+fn ctr_detects_counter_overflow() {
+    let ice = Ice::new(0, &KEY8);
+    let mut ctr = Ctr::with_split(&ice, 1, 4, 0);
+    let mut data = [0u8; 17 * 8];
+    assert_eq!(
+        ctr.apply_keystream(&mut data),
+        Err(IceError::CounterOverflow)
+    );
+    assert_eq!(data, [0u8; 17 * 8]);
+
+    assert_eq!(ctr.apply_keystream(&mut data[..16 * 8]), Ok(()));
+    assert_eq!(
+        ctr.apply_keystream(&mut [0u8; 1]),
+        Err(IceError::CounterOverflow)
+    );
+}
+
+#[test]
+fn ctr_detects_overflow_of_full_64_bit_counter() {
+    let ice = Ice::new(0, &KEY8);
+    let mut ctr = Ctr::new(&ice, [0; 8]);
+    ctr.seek(u64::MAX);
+    assert_eq!(ctr.apply_keystream(&mut [0u8; 8]), Ok(()));
+    assert_eq!(ctr.position(), (1u128 << 64) * 8);
+
+    for len in [1, 8, 17] {
+        let mut data = vec![0u8; len];
+        assert_eq!(
+            ctr.apply_keystream(&mut data),
+            Err(IceError::CounterOverflow),
+            "{len} bytes"
+        );
+        assert!(data.iter().all(|&b| b == 0));
+    }
+    assert_eq!(ctr.position(), (1u128 << 64) * 8);
+}
+
+#[test]
+#[should_panic]
+fn ctr_rejects_oversized_nonce() {
+    let ice = Ice::new(0, &KEY8);
+    Ctr::with_split(&ice, 1 << 32, 32, 0);
+}
@@ -0,0 +1,88 @@
+// EAX known answers were generated with ice-reference/ice.c's `ice_key_encrypt`.
+use icefast::modes::Eax;
+use icefast::{Ice, IceError};
+
+mod common;
+use common::KEY8;
+
+static NONCE: &[u8] = b"nonce123456";
+static AD: &[u8] = b"header";
+static PLAIN_TEXT_26: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+static EAX_CIPHER_TEXT_26_LEVEL0: [u8; 26] = [
+    50, 187, 142, 137, 57, 19, 20, 17, 64, 201, 33, 8, 212, 84, 127, 195, 93, 88, 37, 76, 233, 195,
+    35, 26, 123, 11,
+];
+static EAX_TAG_LEVEL0: [u8; 8] = [133, 243, 169, 209, 130, 25, 202, 103];
+
+#[test]
+fn eax_seal_level0() {
+    let ice = Ice::new(0, &KEY8);
+    let eax = Eax::new(&ice);
+
+    let mut data = PLAIN_TEXT_26.to_vec();
+    let tag = eax.seal_detached(NONCE, AD, &mut data);
+    assert_eq!(data, EAX_CIPHER_TEXT_26_LEVEL0);
+    assert_eq!(*tag, EAX_TAG_LEVEL0);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut data = PLAIN_TEXT_26.to_vec();
+        eax.seal(NONCE, AD, &mut data);
+        assert_eq!(data[..26], EAX_CIPHER_TEXT_26_LEVEL0);
+        assert_eq!(data[26..], EAX_TAG_LEVEL0);
+
+        eax.open(NONCE, AD, &mut data).unwrap();
+        assert_eq!(data, PLAIN_TEXT_26);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn eax_open_rejects_tampering() {
+    let ice = Ice::new(0, &KEY8);
+    let eax = Eax::new(&ice);
+    let mut sealed = EAX_CIPHER_TEXT_26_LEVEL0.to_vec();
+    sealed.extend_from_slice(&EAX_TAG_LEVEL0);
+
+    let mut data = sealed.clone();
+    data[3] ^= 0x10;
+    let tampered = data.clone();
+    assert_eq!(eax.open(NONCE, AD, &mut data), Err(IceError::TagMismatch));
+    assert_eq!(data, tampered);
+
+    let mut data = sealed.clone();
+    assert_eq!(
+        eax.open(NONCE, b"Header", &mut data),
+        Err(IceError::TagMismatch)
+    );
+    assert_eq!(
+        eax.open(b"nonce123457", AD, &mut data),
+        Err(IceError::TagMismatch)
+    );
+    assert_eq!(data, sealed);
+
+    let mut short = vec![0u8; 5];
+    assert_eq!(
+        eax.open(NONCE, AD, &mut short),
+        Err(IceError::InputTooShort { min: 8, actual: 5 })
+    );
+}
+
+#[test]
+fn eax_truncated_tag_round_trip() {
+    let ice = Ice::new(2, &[0x3Cu8; 16]);
+    let eax = Eax::with_tag_len(&ice, 4);
+    let original: Vec<u8> = (0..100_003u32).map(|i| (i * 7) as u8).collect();
+
+    let mut data = original.clone();
+    let tag = eax.seal_detached(b"", b"", &mut data);
+    assert_eq!(tag.len(), 4);
+    assert_ne!(data, original);
+
+    assert_eq!(
+        eax.open_detached(b"", b"", &mut data, &tag[..3]),
+        Err(IceError::TagMismatch)
+    );
+    eax.open_detached(b"", b"", &mut data, &tag).unwrap();
+    assert_eq!(data, original);
+}
@@ -0,0 +1,140 @@
+// CFB, OFB and PCBC known answers were generated with ice-reference/ice.c's `ice_key_encrypt`.
+use icefast::modes::{Cfb, Cfb8, Ofb, Pcbc};
+use icefast::{Ice, IceError};
+
+mod common;
+use common::{IV, KEY8};
+
+static PLAIN_TEXT_27: &str = "feedback modes are serial!!";
+static CFB64_CIPHER_TEXT_27_LEVEL0: [u8; 27] = [
+    96, 44, 29, 209, 79, 139, 121, 26, 223, 134, 146, 62, 129, 217, 159, 171, 171, 195, 164, 30,
+    123, 253, 55, 7, 224, 224, 214,
+];
+static CFB8_CIPHER_TEXT_27_LEVEL0: [u8; 27] = [
+    96, 180, 83, 132, 234, 211, 69, 139, 234, 217, 212, 59, 42, 229, 246, 120, 126, 62, 56, 213,
+    33, 172, 160, 164, 180, 240, 171,
+];
+static OFB_CIPHER_TEXT_27_LEVEL0: [u8; 27] = [
+    96, 44, 29, 209, 79, 139, 121, 26, 210, 99, 40, 142, 11, 97, 81, 69, 218, 235, 108, 168, 232,
+    245, 185, 4, 252, 62, 159,
+];
+static PCBC_CIPHER_TEXT_24_LEVEL0: [u8; 24] = [
+    222, 26, 240, 180, 90, 184, 245, 133, 61, 154, 246, 92, 89, 131, 141, 164, 16, 231, 65, 49,
+    164, 205, 96, 112,
+];
+
+fn unaligned_plaintext() -> Vec<u8> {
+    (0..70_001u32).map(|i| (i * 31) as u8).collect()
+}
+
+#[test]
+fn cfb64_level0() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = PLAIN_TEXT_27.as_bytes().to_owned();
+    Cfb::new(&ice, IV).encrypt(&mut data);
+    assert_eq!(data, CFB64_CIPHER_TEXT_27_LEVEL0);
+    Cfb::new(&ice, IV).decrypt(&mut data);
+    assert_eq!(data, PLAIN_TEXT_27.as_bytes());
+}
+
+#[test]
+fn cfb8_level0() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = PLAIN_TEXT_27.as_bytes().to_owned();
+    Cfb8::new(&ice, IV).encrypt(&mut data);
+    assert_eq!(data, CFB8_CIPHER_TEXT_27_LEVEL0);
+    Cfb8::new(&ice, IV).decrypt(&mut data);
+    assert_eq!(data, PLAIN_TEXT_27.as_bytes());
+}
+
+#[test]
+fn ofb_level0() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = PLAIN_TEXT_27.as_bytes().to_owned();
+    Ofb::new(&ice, IV).apply_keystream(&mut data);
+    assert_eq!(data, OFB_CIPHER_TEXT_27_LEVEL0);
+    Ofb::new(&ice, IV).apply_keystream(&mut data);
+    assert_eq!(data, PLAIN_TEXT_27.as_bytes());
+}
+
+#[test]
+fn pcbc_level0() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = PLAIN_TEXT_27.as_bytes()[..24].to_owned();
+    Pcbc::new(&ice, IV).encrypt(&mut data).unwrap();
+    assert_eq!(data, PCBC_CIPHER_TEXT_24_LEVEL0);
+
+    let mut pcbc = Pcbc::new(&ice, IV);
+    pcbc.decrypt(&mut data[..8]).unwrap();
+    pcbc.decrypt(&mut data[8..]).unwrap();
+    assert_eq!(data, PLAIN_TEXT_27.as_bytes()[..24]);
+    assert_eq!(
+        pcbc.decrypt(&mut [0u8; 5]),
+        Err(IceError::NotBlockAligned(5))
+    );
+}
+
+#[test]
+fn cfb64_streaming_and_parallel_match_one_shot() {
+    let ice = Ice::new(1, &KEY8);
+    let plain = unaligned_plaintext();
+    let mut one_shot = plain.clone();
+    Cfb::new(&ice, IV).encrypt(&mut one_shot);
+
+    let mut streamed = plain.clone();
+    let mut cfb = Cfb::new(&ice, IV);
+    for piece in streamed.chunks_mut(1_237) {
+        cfb.encrypt(piece);
+    }
+    assert_eq!(streamed, one_shot);
+
+    let mut cfb = Cfb::new(&ice, IV);
+    let (head, tail) = streamed.split_at_mut(5);
+    cfb.decrypt(head);
+    #[cfg(feature = "parallel")]
+    cfb.decrypt_par(tail);
+    #[cfg(not(feature = "parallel"))]
+    cfb.decrypt(tail);
+    assert_eq!(streamed, plain);
+
+    let mut data = one_shot.clone();
+    Cfb::new(&ice, IV).decrypt_auto(&mut data);
+    assert_eq!(data, plain);
+}
+
+#[test]
+fn cfb8_streaming_and_parallel_match_one_shot() {
+    let ice = Ice::new(0, &KEY8);
+    let plain = unaligned_plaintext();
+    let mut one_shot = plain.clone();
+    Cfb8::new(&ice, IV).encrypt(&mut one_shot);
+
+    let mut data = one_shot.clone();
+    let mut cfb8 = Cfb8::new(&ice, IV);
+    let (head, tail) = data.split_at_mut(3);
+    cfb8.decrypt(head);
+    #[cfg(feature = "parallel")]
+    cfb8.decrypt_par(tail);
+    #[cfg(not(feature = "parallel"))]
+    cfb8.decrypt(tail);
+    assert_eq!(data, plain);
+
+    let mut data = one_shot.clone();
+    Cfb8::new(&ice, IV).decrypt_auto(&mut data);
+    assert_eq!(data, plain);
+}
+
+#[test]
+fn ofb_streaming_matches_one_shot() {
+    let ice = Ice::new(2, &[0x17u8; 16]);
+    let plain = unaligned_plaintext();
+    let mut one_shot = plain.clone();
+    Ofb::new(&ice, IV).apply_keystream(&mut one_shot);
+
+    let mut streamed = plain.clone();
+    let mut ofb = Ofb::new(&ice, IV);
+    for piece in streamed.chunks_mut(999) {
+        ofb.apply_keystream(piece);
+    }
+    assert_eq!(streamed, one_shot);
+}
@@ -4,9 +4,6 @@
 // #[global_allocator]
 // static GLOBAL: MiMalloc = MiMalloc;
 
-#[path = "../src/icefast.rs"]
-mod icefast;
-
 static KEY8: [u8; 8] = [0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00];
 static KEY16: [u8; 16] = [
     0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00, 0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00,
@@ -131,6 +128,7 @@ fn decrypt_key16_fast_level2() {
     assert_eq!(plaintext, EXPECT_TEXT_16);
 }
 
+#[cfg(feature = "parallel")]
 #[test]
 fn decrypt_key16_fast_par_level0() {
     let test_ice = icefast::Ice::new(0, &KEY8);
@@ -140,6 +138,7 @@ fn decrypt_key16_fast_par_level0() {
     assert_eq!(plaintext, EXPECT_TEXT_16);
 }
 
+#[cfg(feature = "parallel")]
 #[test]
 fn encrypt_key16_fast_par_level1() {
     let test_ice = icefast::Ice::new(1, &KEY8);
@@ -148,6 +147,7 @@ fn encrypt_key16_fast_par_level1() {
     assert_eq!(data, CIPHER_TEXT_16_LEVEL1);
 }
 
+#[cfg(feature = "parallel")]
 #[test]
 fn decrypt_key16_fast_par_level1() {
     let test_ice = icefast::Ice::new(1, &KEY8);
@@ -158,6 +158,7 @@ fn decrypt_key16_fast_par_level1() {
     assert_eq!(plaintext, EXPECT_TEXT_16);
 }
 
+#[cfg(feature = "parallel")]
 #[test]
 fn encrypt_key16_fast_par_level2() {
     let test_ice = icefast::Ice::new(2, &KEY16);
@@ -166,6 +167,7 @@ fn encrypt_key16_fast_par_level2() {
     assert_eq!(data, CIPHER_TEXT_16_LEVEL2);
 }
 
+#[cfg(feature = "parallel")]
 #[test]
 fn decrypt_key16_fast_par_level2() {
     let test_ice = icefast::Ice::new(2, &KEY16);
@@ -174,3 +176,130 @@ fn decrypt_key16_fast_par_level2() {
     let plaintext = String::from_utf8(data.to_vec()).unwrap();
     assert_eq!(plaintext, EXPECT_TEXT_16);
 }
+
+#[test]
+fn subkeys_match_level_rounds() {
+    assert_eq!(icefast::Ice::new(0, &KEY8).key.subkeys().len(), 8);
+    assert_eq!(icefast::Ice::new(1, &KEY8).key.subkeys().len(), 16);
+    assert_eq!(icefast::Ice::new(2, &KEY16).key.subkeys().len(), 32);
+}
+
+#[test]
+fn encrypt_key16_fixed_levels() {
+    let mut data = EXPECT_TEXT_16.as_bytes().to_owned();
+    icefast::IceThin::new(&KEY8).encrypt(&mut data);
+    assert_eq!(data, CIPHER_TEXT_16_LEVEL0);
+
+    #[cfg(feature = "parallel")]
+    {
+        let mut data = EXPECT_TEXT_16.as_bytes().to_owned();
+        icefast::Ice1::new(&KEY8).encrypt_par(&mut data);
+        assert_eq!(data, CIPHER_TEXT_16_LEVEL1);
+    }
+
+    let mut data = EXPECT_TEXT_16.as_bytes().to_owned();
+    icefast::Ice2::new(&KEY16).encrypt_auto(&mut data);
+    assert_eq!(data, CIPHER_TEXT_16_LEVEL2);
+}
+
+#[test]
+fn decrypt_key16_fixed_levels() {
+    let mut data = CIPHER_TEXT_16_LEVEL0.to_owned();
+    icefast::IceThin::new(&KEY8).decrypt(&mut data);
+    assert_eq!(data, EXPECT_TEXT_16.as_bytes());
+
+    #[cfg(feature = "parallel")]
+    {
+        let mut data = CIPHER_TEXT_16_LEVEL1.to_owned();
+        icefast::Ice1::new(&KEY8).decrypt_par(&mut data);
+        assert_eq!(data, EXPECT_TEXT_16.as_bytes());
+    }
+
+    let mut data = CIPHER_TEXT_16_LEVEL2.to_owned();
+    icefast::Ice2::new(&KEY16).decrypt_auto(&mut data);
+    assert_eq!(data, EXPECT_TEXT_16.as_bytes());
+}
+
+#[test]
+fn encrypt_to_key16_fast_level0() {
+    let test_ice = icefast::Ice::new(0, &KEY8);
+    let src = EXPECT_TEXT_16.as_bytes();
+    let mut dst = [0u8; 16];
+    test_ice.encrypt_to(src, &mut dst);
+    assert_eq!(dst, CIPHER_TEXT_16_LEVEL0);
+    assert_eq!(src, EXPECT_TEXT_16.as_bytes());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn decrypt_to_key16_fast_par_level2() {
+    let test_ice = icefast::Ice::new(2, &KEY16);
+    let mut dst = [0u8; 16];
+    test_ice.decrypt_par_to(&CIPHER_TEXT_16_LEVEL2, &mut dst);
+    assert_eq!(dst, EXPECT_TEXT_16.as_bytes());
+}
+
+#[test]
+fn encrypt_auto_to_matches_in_place() {
+    let test_ice = icefast::Ice::new(1, &KEY8);
+    let src: Vec<u8> = (0..65_544u32).map(|i| i as u8).collect();
+    let mut expected = src.clone();
+    test_ice.encrypt_auto(&mut expected);
+
+    let mut dst = vec![0u8; src.len()];
+    test_ice.encrypt_auto_to(&src, &mut dst);
+    assert_eq!(dst, expected);
+
+    let mut plain = vec![0u8; src.len()];
+    test_ice.decrypt_auto_to(&dst, &mut plain);
+    assert_eq!(plain, src);
+}
+
+#[test]
+#[should_panic]
+fn encrypt_to_rejects_length_mismatch() {
+    let test_ice = icefast::Ice::new(0, &KEY8);
+    let mut dst = [0u8; 8];
+    test_ice.encrypt_to(EXPECT_TEXT_16.as_bytes(), &mut dst);
+}
+
+#[test]
+fn encrypt_block_key8_fast_level1() {
+    let test_ice = icefast::Ice::new(1, &KEY8);
+    let block: [u8; 8] = EXPECT_TEXT_8.as_bytes().try_into().unwrap();
+    assert_eq!(test_ice.encrypt_block(block), CIPHER_TEXT_8_LEVEL1);
+    assert_eq!(test_ice.decrypt_block(CIPHER_TEXT_8_LEVEL1), block);
+}
+
+#[test]
+fn encrypt_u64_and_lr_key8_fast_level2() {
+    let test_ice = icefast::Ice2::new(&KEY16);
+    let plain = u64::from_be_bytes(EXPECT_TEXT_8.as_bytes().try_into().unwrap());
+    let cipher = u64::from_be_bytes(CIPHER_TEXT_8_LEVEL2);
+    assert_eq!(test_ice.encrypt_u64(plain), cipher);
+    assert_eq!(test_ice.decrypt_u64(cipher), plain);
+
+    let (l, r) = test_ice.encrypt_lr((plain >> 32) as u32, plain as u32);
+    assert_eq!((l, r), ((cipher >> 32) as u32, cipher as u32));
+    assert_eq!(
+        test_ice.decrypt_lr(l, r),
+        ((plain >> 32) as u32, plain as u32)
+    );
+}
+
+#[test]
+fn set_key_matches_new() {
+    let mut test_ice = icefast::Ice::new(2, &[0xAAu8; 16]);
+    assert!(test_ice.set_key(&KEY8).is_err());
+    test_ice.set_key(&KEY16).unwrap();
+    let mut data = EXPECT_TEXT_16.as_bytes().to_owned();
+    test_ice.encrypt(&mut data);
+    assert_eq!(data, CIPHER_TEXT_16_LEVEL2);
+
+    let mut test_ice = icefast::IceThin::new(&[0x55u8; 8]);
+    test_ice.set_key(&KEY8);
+    assert_eq!(
+        test_ice.encrypt_block(EXPECT_TEXT_8.as_bytes().try_into().unwrap()),
+        CIPHER_TEXT_8_LEVEL0
+    );
+}
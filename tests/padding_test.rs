@@ -0,0 +1,143 @@
+#![cfg(feature = "alloc")]
+
+use icefast::{Ice, Padding, PaddingError};
+
+mod common;
+use common::{CIPHER_TEXT_16_LEVEL0, EXPECT_TEXT_16, KEY8};
+
+static SCHEMES: [Padding; 5] = [
+    Padding::Pkcs7,
+    Padding::AnsiX923,
+    Padding::Iso10126,
+    Padding::Iso7816,
+    Padding::Zero,
+];
+
+/// Encrypts `data` with `padding` and returns the raw decryption, padding included.
+fn padded_plaintext(ice: &Ice, data: &[u8], padding: Padding) -> Vec<u8> {
+    let mut out = ice.encrypt_padded_vec(data, padding);
+    ice.decrypt(&mut out);
+    out
+}
+
+#[test]
+fn pkcs7_appends_full_block_when_aligned() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = EXPECT_TEXT_16.as_bytes().to_vec();
+    ice.encrypt_padded(&mut data, Padding::Pkcs7);
+    assert_eq!(data.len(), 24);
+    assert_eq!(data[..16], CIPHER_TEXT_16_LEVEL0);
+    assert_eq!(data[16..], ice.encrypt_block([8; 8]));
+
+    assert_eq!(
+        ice.decrypt_padded(&mut data, Padding::Pkcs7),
+        Ok(EXPECT_TEXT_16.as_bytes())
+    );
+}
+
+#[test]
+fn padding_bytes_match_scheme() {
+    let ice = Ice::new(1, &KEY8);
+    let data = b"abcde";
+    assert_eq!(
+        padded_plaintext(&ice, data, Padding::Pkcs7),
+        b"abcde\x03\x03\x03"
+    );
+    assert_eq!(
+        padded_plaintext(&ice, data, Padding::AnsiX923),
+        b"abcde\x00\x00\x03"
+    );
+    assert_eq!(
+        padded_plaintext(&ice, data, Padding::Iso7816),
+        b"abcde\x80\x00\x00"
+    );
+    assert_eq!(
+        padded_plaintext(&ice, data, Padding::Zero),
+        b"abcde\x00\x00\x00"
+    );
+    let iso10126 = padded_plaintext(&ice, data, Padding::Iso10126);
+    assert_eq!(iso10126[..5], *data);
+    assert_eq!(iso10126[7], 3);
+
+    assert!(ice.encrypt_padded_vec(b"", Padding::Zero).is_empty());
+    assert_eq!(
+        padded_plaintext(&ice, b"", Padding::Iso7816),
+        [0x80, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn iso10126_filler_is_fresh_per_call() {
+    let ice = Ice::new(1, &KEY8);
+    let first = padded_plaintext(&ice, b"a", Padding::Iso10126);
+    let second = padded_plaintext(&ice, b"a", Padding::Iso10126);
+    assert_eq!(first[7], second[7]);
+    assert_ne!(first[1..7], second[1..7]);
+}
+
+#[test]
+fn padded_round_trip() {
+    let ice = Ice::new(2, &[0x3Cu8; 16]);
+    for padding in SCHEMES {
+        for len in [0, 1, 7, 8, 9, 40_000, 40_003] {
+            // Zero padding cannot restore trailing zeros, so the data avoids them.
+            let original: Vec<u8> = (0..len).map(|i| (i % 251 + 1) as u8).collect();
+
+            let mut data = original.clone();
+            ice.encrypt_padded(&mut data, padding);
+            assert_eq!(data.len() % 8, 0);
+            assert_eq!(ice.decrypt_padded(&mut data, padding).unwrap(), original);
+
+            let ciphertext = ice.encrypt_padded_vec(&original, padding);
+            assert_eq!(
+                ice.decrypt_padded_vec(&ciphertext, padding).unwrap(),
+                original
+            );
+        }
+    }
+}
+
+#[test]
+fn invalid_padding_is_rejected() {
+    let ice = Ice::new(0, &KEY8);
+    let invalid: [&[u8; 8]; 6] = [
+        b"abcdefg\x00",
+        b"abcdefg\x09",
+        b"abcde\x03\x02\x03",
+        b"abcdefgh",
+        b"abcd\x00\x01\x00\x03",
+        b"\x00\x00\x00\x00\x00\x00\x00\x00",
+    ];
+    for block in invalid {
+        let ciphertext = ice.encrypt_block(*block);
+        for padding in [Padding::Pkcs7, Padding::AnsiX923, Padding::Iso7816] {
+            assert_eq!(
+                ice.decrypt_padded_vec(&ciphertext, padding),
+                Err(PaddingError::Invalid),
+                "{padding:?} accepted {block:?}"
+            );
+        }
+    }
+
+    let mut empty: [u8; 0] = [];
+    assert_eq!(
+        ice.decrypt_padded(&mut empty, Padding::Pkcs7),
+        Err(PaddingError::Invalid)
+    );
+    assert_eq!(ice.decrypt_padded(&mut empty, Padding::Zero), Ok(&[][..]));
+}
+
+#[test]
+fn unaligned_input_is_rejected() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = [7u8; 13];
+    assert_eq!(
+        ice.decrypt_padded(&mut data, Padding::Pkcs7),
+        Err(PaddingError::NotBlockAligned(13))
+    );
+    assert_eq!(data, [7u8; 13]);
+    assert_eq!(
+        ice.decrypt_padded_vec(&data, Padding::Zero),
+        Err(PaddingError::NotBlockAligned(13))
+    );
+}
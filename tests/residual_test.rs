@@ -0,0 +1,82 @@
+use icefast::{Ice, IceError, IceThin, Residual};
+
+mod common;
+use common::{CIPHER_TEXT_16_LEVEL0, EXPECT_TEXT_16, KEY8};
+
+#[test]
+fn leave_plain_copies_trailing_bytes() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = b"abcdefghijklmnopxyz".to_vec();
+    ice.encrypt_auto_with(&mut data, Residual::LeavePlain)
+        .unwrap();
+    assert_eq!(data[..16], CIPHER_TEXT_16_LEVEL0);
+    assert_eq!(&data[16..], b"xyz");
+
+    ice.decrypt_auto_with(&mut data, Residual::LeavePlain)
+        .unwrap();
+    assert_eq!(data, b"abcdefghijklmnopxyz");
+
+    let mut short = *b"xyz";
+    ice.encrypt_auto_with(&mut short, Residual::LeavePlain)
+        .unwrap();
+    assert_eq!(&short, b"xyz");
+}
+
+#[test]
+fn error_leaves_buffer_untouched() {
+    let ice = IceThin::new(&KEY8);
+    let mut data = [7u8; 13];
+    assert_eq!(
+        ice.encrypt_auto_with(&mut data, Residual::Error),
+        Err(IceError::NotBlockAligned(13))
+    );
+    assert_eq!(data, [7u8; 13]);
+
+    let mut empty: [u8; 0] = [];
+    assert_eq!(ice.decrypt_auto_with(&mut empty, Residual::Error), Ok(()));
+
+    let mut aligned = EXPECT_TEXT_16.as_bytes().to_owned();
+    ice.encrypt_auto_with(&mut aligned, Residual::Error)
+        .unwrap();
+    assert_eq!(aligned, CIPHER_TEXT_16_LEVEL0);
+}
+
+#[test]
+#[should_panic]
+fn panic_rejects_unaligned_input() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = [0u8; 13];
+    let _ = ice.encrypt_auto_with(&mut data, Residual::Panic);
+}
+
+#[test]
+fn xor_with_keystream_uses_last_ciphertext_block() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data = b"abcdefghijklmnopxyz".to_vec();
+    ice.encrypt_auto_with(&mut data, Residual::XorWithKeystream)
+        .unwrap();
+    assert_eq!(data[..16], CIPHER_TEXT_16_LEVEL0);
+
+    let keystream = ice.encrypt_block(CIPHER_TEXT_16_LEVEL0[8..].try_into().unwrap());
+    let tail: Vec<u8> = b"xyz".iter().zip(keystream).map(|(b, k)| b ^ k).collect();
+    assert_eq!(data[16..], tail);
+
+    ice.decrypt_auto_with(&mut data, Residual::XorWithKeystream)
+        .unwrap();
+    assert_eq!(data, b"abcdefghijklmnopxyz");
+}
+
+#[test]
+fn xor_with_keystream_round_trips_large_buffers() {
+    let ice = Ice::new(2, &[0x3Cu8; 16]);
+    for len in [5, 8, 40_000, 40_003, 40_007] {
+        let original: Vec<u8> = (0..len).map(|i| (i * 31 + 7) as u8).collect();
+        let mut data = original.clone();
+        ice.encrypt_auto_with(&mut data, Residual::XorWithKeystream)
+            .unwrap();
+        assert_ne!(data, original);
+        ice.decrypt_auto_with(&mut data, Residual::XorWithKeystream)
+            .unwrap();
+        assert_eq!(data, original);
+    }
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "rustcrypto")]
+
+use cbc::cipher::{Block, KeyInit, KeyIvInit, StreamCipher};
+use cbc::cipher::{BlockCipherDecrypt, BlockCipherEncrypt, BlockModeDecrypt, BlockModeEncrypt};
+use icefast::modes::{Cbc, Ctr};
+use icefast::{Ice, Ice1, Ice2, Ice8, IceThin};
+
+mod common;
+use common::{CIPHER_TEXT_16_LEVEL0, EXPECT_TEXT_16, IV, KEY8, large_plaintext};
+
+// Long enough to exercise the batched backend and its tail.
+fn odd_plaintext() -> Vec<u8> {
+    let mut plaintext = large_plaintext();
+    plaintext.truncate(8 * 1003);
+    plaintext
+}
+
+#[test]
+fn block_cipher_level0() {
+    let ice = <IceThin as KeyInit>::new(&KEY8.into());
+    let mut blocks: Vec<Block<IceThin>> = EXPECT_TEXT_16
+        .as_bytes()
+        .chunks(8)
+        .map(|c| c.try_into().unwrap())
+        .collect();
+
+    ice.encrypt_blocks(&mut blocks);
+    assert_eq!(blocks.concat(), CIPHER_TEXT_16_LEVEL0);
+    ice.decrypt_blocks(&mut blocks);
+    assert_eq!(blocks.concat(), EXPECT_TEXT_16.as_bytes());
+
+    let mut block = CIPHER_TEXT_16_LEVEL0[..8].try_into().unwrap();
+    BlockCipherDecrypt::decrypt_block(&ice, &mut block);
+    assert_eq!(block[..], EXPECT_TEXT_16.as_bytes()[..8]);
+}
+
+#[test]
+fn batched_blocks_match_native() {
+    let key16: [u8; 16] = core::array::from_fn(|i| (i * 11) as u8);
+    let key64: [u8; 64] = core::array::from_fn(|i| (i * 37 + 11) as u8);
+    let plaintext = odd_plaintext();
+
+    let mut expected = plaintext.clone();
+    Ice2::new(&key16).encrypt(&mut expected);
+    let mut blocks: Vec<_> = plaintext.chunks(8).map(|c| c.try_into().unwrap()).collect();
+    <Ice2 as KeyInit>::new(&key16.into()).encrypt_blocks(&mut blocks);
+    assert_eq!(blocks.concat(), expected);
+
+    let mut expected = plaintext.clone();
+    Ice::new(8, &key64).encrypt(&mut expected);
+    let mut blocks: Vec<_> = plaintext.chunks(8).map(|c| c.try_into().unwrap()).collect();
+    <Ice8 as KeyInit>::new(&key64.into()).encrypt_blocks(&mut blocks);
+    assert_eq!(blocks.concat(), expected);
+    Ice::new(8, &key64).decrypt_blocks(&mut blocks);
+    assert_eq!(blocks.concat(), plaintext);
+}
+
+#[test]
+fn cbc_crate_matches_native() {
+    let ice = Ice::new(1, &KEY8);
+    let plaintext = odd_plaintext();
+    let mut expected = plaintext.clone();
+    Cbc::new(&ice, IV).encrypt(&mut expected).unwrap();
+
+    let mut blocks: Vec<_> = plaintext.chunks(8).map(|c| c.try_into().unwrap()).collect();
+    cbc::Encryptor::<Ice1>::new(&KEY8.into(), &IV.into()).encrypt_blocks(&mut blocks);
+    assert_eq!(blocks.concat(), expected);
+
+    cbc::Decryptor::<Ice1>::new(&KEY8.into(), &IV.into()).decrypt_blocks(&mut blocks);
+    assert_eq!(blocks.concat(), plaintext);
+}
+
+#[test]
+fn ctr_crate_matches_native() {
+    let ice = Ice::new(1, &KEY8);
+    let plaintext = odd_plaintext();
+    let mut expected = plaintext[..8001].to_vec();
+    Ctr::new(&ice, IV).apply_keystream(&mut expected).unwrap();
+
+    let mut data = plaintext[..8001].to_vec();
+    ctr::Ctr64BE::<Ice1>::new(&KEY8.into(), &IV.into()).apply_keystream(&mut data);
+    assert_eq!(data, expected);
+}
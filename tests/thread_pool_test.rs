@@ -0,0 +1,163 @@
+#![cfg(feature = "parallel")]
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+use icefast::{Ice, Ice2};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+mod common;
+use common::{CIPHER_TEXT_16_LEVEL1, EXPECT_TEXT_16, KEY8, large_plaintext};
+
+/// How long an `op` that needs the blocked pool is given to finish anyway. Only a broken
+/// dispatch could finish, so a slow runner can make the check pass but never fail.
+const WAITS_FOR_POOL: Duration = Duration::from_millis(500);
+
+/// How long an `op` that must not need the pool is given to finish. This only bounds a hang
+/// when the check fails; a passing run returns as soon as the serial work is done.
+const RUNS_WITHOUT_POOL: Duration = Duration::from_secs(60);
+
+/// Runs `op` while every worker of `pool` is blocked, and returns whether it finished within
+/// `wait`. Work that `op` sends to `pool` can only start once the workers are released.
+fn finishes_while_pool_is_busy(
+    pool: &ThreadPool,
+    wait: Duration,
+    op: impl FnOnce() + Send,
+) -> bool {
+    let threads = pool.current_num_threads();
+    let started = Arc::new(Barrier::new(threads + 1));
+    let release = Arc::new(Barrier::new(threads + 1));
+    for _ in 0..threads {
+        let (started, release) = (started.clone(), release.clone());
+        pool.spawn(move || {
+            started.wait();
+            release.wait();
+        });
+    }
+    started.wait();
+
+    let (done_tx, done_rx) = mpsc::channel();
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            op();
+            done_tx.send(()).unwrap();
+        });
+        let finished = match done_rx.recv_timeout(wait) {
+            Ok(()) => true,
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => panic!("op panicked"),
+        };
+        release.wait();
+        finished
+    })
+}
+
+#[test]
+fn par_in_level1() {
+    let pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    let ice = Ice::new(1, &KEY8);
+
+    let mut data = EXPECT_TEXT_16.as_bytes().to_owned();
+    ice.encrypt_par_in(&pool, &mut data);
+    assert_eq!(data, CIPHER_TEXT_16_LEVEL1);
+    ice.decrypt_par_in(&pool, &mut data);
+    assert_eq!(data, EXPECT_TEXT_16.as_bytes());
+}
+
+#[test]
+fn pool_variants_match_serial() {
+    let ice = Ice2::new(&[0x3Cu8; 16]);
+    let plain = large_plaintext();
+    let mut expected = plain.clone();
+    ice.encrypt(&mut expected);
+
+    for threads in [1, 3] {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+
+        let mut data = plain.clone();
+        ice.encrypt_par_in(&pool, &mut data);
+        assert_eq!(data, expected, "{threads} threads");
+        ice.decrypt_auto_in(&pool, &mut data);
+        assert_eq!(data, plain, "{threads} threads");
+
+        let mut data = plain.clone();
+        ice.encrypt_auto_in(&pool, &mut data);
+        assert_eq!(data, expected, "{threads} threads");
+        ice.decrypt_par_in(&pool, &mut data);
+        assert_eq!(data, plain, "{threads} threads");
+    }
+}
+
+#[test]
+fn pool_variants_nest_inside_pool() {
+    let pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    let ice = Ice::new(1, &KEY8);
+    let plain = large_plaintext();
+    let mut expected = plain.clone();
+    ice.encrypt(&mut expected);
+
+    let mut data = plain.clone();
+    pool.install(|| ice.encrypt_auto_in(&pool, &mut data));
+    assert_eq!(data, expected);
+}
+
+#[test]
+fn par_in_runs_on_the_supplied_pool() {
+    let ice = Ice::new(1, &KEY8);
+    for threads in [1, 2] {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let mut data = EXPECT_TEXT_16.as_bytes().to_owned();
+        assert!(!finishes_while_pool_is_busy(&pool, WAITS_FOR_POOL, || ice
+            .encrypt_par_in(&pool, &mut data)));
+        assert_eq!(data, CIPHER_TEXT_16_LEVEL1);
+        assert!(!finishes_while_pool_is_busy(&pool, WAITS_FOR_POOL, || ice
+            .decrypt_par_in(&pool, &mut data)));
+        assert_eq!(data, EXPECT_TEXT_16.as_bytes());
+    }
+}
+
+#[test]
+fn auto_in_decides_by_the_supplied_pool() {
+    let ice = Ice::builder()
+        .level(1)
+        .key(&KEY8)
+        .parallel_threshold(1024)
+        .build()
+        .unwrap();
+    let plain = large_plaintext();
+    let mut expected = plain.clone();
+    ice.encrypt(&mut expected);
+
+    // A single-threaded pool keeps large inputs serial, whatever the global pool's size.
+    let single = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let mut data = plain.clone();
+    assert!(finishes_while_pool_is_busy(
+        &single,
+        RUNS_WITHOUT_POOL,
+        || ice.encrypt_auto_in(&single, &mut data)
+    ));
+    assert_eq!(data, expected);
+
+    // With two threads the same input goes to the pool, even if the global pool has one.
+    let pair = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    assert!(!finishes_while_pool_is_busy(&pair, WAITS_FOR_POOL, || ice
+        .decrypt_auto_in(&pair, &mut data)));
+    assert_eq!(data, plain);
+
+    // Below the threshold the pool is never used.
+    let mut data = plain[..512].to_vec();
+    assert!(finishes_while_pool_is_busy(
+        &pair,
+        RUNS_WITHOUT_POOL,
+        || ice.encrypt_auto_in(&pair, &mut data)
+    ));
+    assert_eq!(data, expected[..512]);
+}
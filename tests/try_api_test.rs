@@ -0,0 +1,70 @@
+use icefast::{Ice, IceError, IceThin};
+
+static KEY8: [u8; 8] = [0x51, 0xF3, 0x0F, 0x11, 0x04, 0x24, 0x6A, 0x00];
+static EXPECT_TEXT_16: &str = "abcdefghijklmnop";
+static CIPHER_TEXT_16_LEVEL0: [u8; 16] = [
+    195, 233, 103, 103, 181, 234, 50, 163, 218, 3, 22, 226, 147, 169, 252, 216,
+];
+
+#[test]
+fn try_new_reports_bad_arguments() {
+    assert_eq!(
+        Ice::try_new(2, &KEY8).unwrap_err(),
+        IceError::KeyTooShort {
+            expected: 16,
+            actual: 8
+        }
+    );
+    assert_eq!(
+        Ice::try_new(9, &[0u8; 72]).unwrap_err(),
+        IceError::UnsupportedLevel(9)
+    );
+    assert!(Ice::try_new(1, &KEY8).is_ok());
+}
+
+#[test]
+fn try_encrypt_matches_encrypt() {
+    let ice = Ice::try_new(0, &KEY8).unwrap();
+
+    let mut data = EXPECT_TEXT_16.as_bytes().to_owned();
+    ice.try_encrypt(&mut data).unwrap();
+    assert_eq!(data, CIPHER_TEXT_16_LEVEL0);
+    #[cfg(feature = "parallel")]
+    ice.try_decrypt_par(&mut data).unwrap();
+    #[cfg(not(feature = "parallel"))]
+    ice.try_decrypt(&mut data).unwrap();
+    assert_eq!(data, EXPECT_TEXT_16.as_bytes());
+    ice.try_encrypt_auto(&mut data).unwrap();
+    assert_eq!(data, CIPHER_TEXT_16_LEVEL0);
+}
+
+#[test]
+fn try_encrypt_rejects_unaligned_input() {
+    let ice = IceThin::new(&KEY8);
+    let mut data = [7u8; 13];
+    assert_eq!(
+        ice.try_encrypt(&mut data),
+        Err(IceError::NotBlockAligned(13))
+    );
+    #[cfg(feature = "parallel")]
+    assert_eq!(
+        ice.try_decrypt_par(&mut data),
+        Err(IceError::NotBlockAligned(13))
+    );
+    assert_eq!(
+        ice.try_decrypt_auto(&mut data),
+        Err(IceError::NotBlockAligned(13))
+    );
+    assert_eq!(data, [7u8; 13]);
+}
+
+#[test]
+fn try_encrypt_empty_is_noop() {
+    let ice = Ice::new(0, &KEY8);
+    let mut data: [u8; 0] = [];
+    assert_eq!(ice.try_encrypt(&mut data), Ok(()));
+    assert_eq!(ice.try_decrypt(&mut data), Ok(()));
+    #[cfg(feature = "parallel")]
+    assert_eq!(ice.try_encrypt_par(&mut data), Ok(()));
+    assert_eq!(ice.try_decrypt_auto(&mut data), Ok(()));
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "std")]
+
+use icefast::tune::{self, TuneProfile};
+use icefast::{Ice, IceConfig, IceLevel, ProfileError};
+
+static KEY16: [u8; 16] = [0x3C; 16];
+
+#[test]
+fn profile_round_trips_through_text() {
+    let profile: TuneProfile = "\
+        # tuned by hand\n\
+        level 0: parallel_threshold=65536 max_serial_batch_exp=6\n\
+        \n\
+        level 2: parallel_threshold=18446744073709551615 max_parallel_batch_exp=3\n"
+        .parse()
+        .unwrap();
+
+    let thin = profile.level(IceLevel::THIN);
+    assert_eq!(thin.parallel_threshold(), 65_536);
+    assert_eq!(thin.max_serial_batch_exp(), 6);
+    assert_eq!(
+        thin.max_parallel_batch_exp(),
+        IceConfig::MAX_PARALLEL_BATCH_EXP
+    );
+    assert_eq!(
+        profile
+            .level(IceLevel::new(2).unwrap())
+            .parallel_threshold(),
+        usize::MAX
+    );
+    assert_eq!(
+        profile.level(IceLevel::new(1).unwrap()),
+        TuneProfile::default().level(IceLevel::new(1).unwrap())
+    );
+
+    assert_eq!(profile.to_string().parse::<TuneProfile>(), Ok(profile));
+    assert_eq!(
+        TuneProfile::default().to_string().parse(),
+        Ok(TuneProfile::default())
+    );
+}
+
+#[test]
+fn profile_rejects_bad_lines() {
+    let parse = |s: &str| s.parse::<TuneProfile>().unwrap_err();
+    assert_eq!(
+        parse("level 0 parallel_threshold=1"),
+        ProfileError::Malformed { line: 1 }
+    );
+    assert_eq!(
+        parse("\nlevel x: max_serial_batch_exp=1"),
+        ProfileError::Malformed { line: 2 }
+    );
+    assert_eq!(
+        parse("level 0: batch=4"),
+        ProfileError::Malformed { line: 1 }
+    );
+    assert_eq!(
+        parse("level 0: parallel_threshold=-1"),
+        ProfileError::Malformed { line: 1 }
+    );
+    assert_eq!(
+        parse("level 9: parallel_threshold=1"),
+        ProfileError::OutOfRange { line: 1 }
+    );
+    assert_eq!(
+        parse("level 1: max_serial_batch_exp=10"),
+        ProfileError::OutOfRange { line: 1 }
+    );
+    assert_eq!(
+        parse("level 1: max_parallel_batch_exp=9"),
+        ProfileError::OutOfRange { line: 1 }
+    );
+}
+
+#[test]
+fn ice_loads_its_level() {
+    let profile: TuneProfile =
+        "level 2: parallel_threshold=8 max_serial_batch_exp=2 max_parallel_batch_exp=1"
+            .parse()
+            .unwrap();
+    let plain: Vec<u8> = (0..70_000u32).map(|i| (i * 3) as u8).collect();
+    let mut expected = plain.clone();
+    Ice::new(2, &KEY16).encrypt(&mut expected);
+
+    let mut ice = Ice::new(2, &KEY16);
+    ice.load_profile(&profile);
+    assert_eq!(ice.config().parallel_threshold(), 8);
+    assert_eq!(ice.config().max_serial_batch_exp(), 2);
+    assert_eq!(ice.config().max_parallel_batch_exp(), 1);
+
+    let mut data = plain.clone();
+    ice.encrypt_auto(&mut data);
+    assert_eq!(data, expected);
+
+    let mut thin = Ice::new(0, &KEY16);
+    thin.load_profile(&profile);
+    assert_eq!(
+        thin.config().max_serial_batch_exp(),
+        IceConfig::MAX_SERIAL_BATCH_EXP
+    );
+}
+
+#[test]
+fn calibrate_levels_measures_only_those_levels() {
+    let profile = tune::calibrate_levels(&[IceLevel::THIN]);
+    let thin = profile.level(IceLevel::THIN);
+    assert!(thin.max_serial_batch_exp() <= IceConfig::MAX_SERIAL_BATCH_EXP);
+    assert!(thin.max_parallel_batch_exp() <= IceConfig::MAX_PARALLEL_BATCH_EXP);
+    // Never beyond the largest length calibration tries, even if parallel never wins there.
+    assert!(thin.parallel_threshold() <= 1 << 20);
+    for level in 1..=IceLevel::MAX {
+        let level = IceLevel::new(level).unwrap();
+        assert_eq!(profile.level(level), TuneProfile::default().level(level));
+    }
+    assert_eq!(profile.to_string().parse(), Ok(profile));
+}